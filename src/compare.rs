@@ -0,0 +1,274 @@
+pub mod compare {
+    use crate::constraints::constraints::*;
+    use polars::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+    use std::fmt;
+
+    /// Smallest change in a null rate or category share that is reported.
+    pub const SHIFT_THRESHOLD: f64 = 0.01;
+    /// Text columns with more distinct values than this aren't treated as
+    /// categories.
+    pub const MAX_CATEGORIES: usize = 50;
+
+    /// A value in the old and the new file.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Shift<T> {
+        pub old: T,
+        pub new: T,
+    }
+
+    impl<T: PartialEq> Shift<T> {
+        fn changed(old: T, new: T) -> Option<Shift<T>> {
+            (old != new).then_some(Shift { old, new })
+        }
+    }
+
+    /// Share of the rows holding a category in the old and the new file.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct CategoryShift {
+        pub value: String,
+        pub old_share: f64,
+        pub new_share: f64,
+    }
+
+    /// Differences of a column present in both files. Fields are `None`
+    /// when the column didn't change in that respect.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct ColumnComparison {
+        pub name: String,
+        pub data_type: Option<Shift<ColumnType>>,
+        pub null_rate: Option<Shift<f64>>,
+        pub min_value: Option<Shift<Option<f64>>>,
+        pub max_value: Option<Shift<Option<f64>>>,
+        pub distinct_count: Option<Shift<u32>>,
+        pub categories: Vec<CategoryShift>,
+    }
+
+    impl ColumnComparison {
+        pub fn is_empty(&self) -> bool {
+            self.data_type.is_none()
+                && self.null_rate.is_none()
+                && self.min_value.is_none()
+                && self.max_value.is_none()
+                && self.distinct_count.is_none()
+                && self.categories.is_empty()
+        }
+    }
+
+    /// Differences between the profiles of two data files.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct ProfileComparison {
+        pub rows: Shift<usize>,
+        pub added_columns: Vec<String>,
+        pub removed_columns: Vec<String>,
+        pub columns: Vec<ColumnComparison>,
+    }
+
+    impl ProfileComparison {
+        pub fn is_empty(&self) -> bool {
+            self.rows.old == self.rows.new
+                && self.added_columns.is_empty()
+                && self.removed_columns.is_empty()
+                && self.columns.is_empty()
+        }
+    }
+
+    fn _null_rate(col: &Series) -> f64 {
+        if col.is_empty() {
+            0.0
+        } else {
+            col.null_count() as f64 / col.len() as f64
+        }
+    }
+
+    /// Share of the non-null values holding each value, or `None` when the
+    /// column has too many distinct values to be a category. Nulls are left
+    /// to the null rate.
+    fn _category_shares(col: &Series) -> Option<BTreeMap<String, f64>> {
+        if col.dtype() != &DataType::Utf8 {
+            return None;
+        }
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for value in col.utf8().ok()?.into_iter().flatten() {
+            *counts.entry(String::from(value)).or_default() += 1;
+        }
+        if counts.len() > MAX_CATEGORIES {
+            return None;
+        }
+        let values = (col.len() - col.null_count()) as f64;
+        Some(counts.into_iter().map(|(value, count)| (value, count as f64 / values)).collect())
+    }
+
+    fn _category_shifts(old: &Series, new: &Series) -> Vec<CategoryShift> {
+        let (Some(old), Some(new)) = (_category_shares(old), _category_shares(new)) else {
+            return vec![];
+        };
+        let mut values: Vec<&String> = old.keys().collect();
+        values.extend(new.keys().filter(|v| !old.contains_key(*v)));
+        values
+            .into_iter()
+            .map(|value| CategoryShift {
+                value: value.clone(),
+                old_share: old.get(value).copied().unwrap_or_default(),
+                new_share: new.get(value).copied().unwrap_or_default(),
+            })
+            .filter(|s| (s.new_share - s.old_share).abs() >= SHIFT_THRESHOLD || s.old_share == 0.0 || s.new_share == 0.0)
+            .collect()
+    }
+
+    fn _compare_column(old: &Constraint, new: &Constraint, old_col: &Series, new_col: &Series) -> ColumnComparison {
+        let old_rate = _null_rate(old_col);
+        let new_rate = _null_rate(new_col);
+        let distinct_count = |col: &Series| aggregate(col, "distinct_count").unwrap_or_default() as u32;
+        ColumnComparison {
+            name: new.name.clone(),
+            data_type: Shift::changed(old.data_type.clone(), new.data_type.clone()),
+            null_rate: (new_rate != old_rate
+                && ((new_rate - old_rate).abs() >= SHIFT_THRESHOLD || old_rate == 0.0 || new_rate == 0.0))
+                .then_some(Shift {
+                old: old_rate,
+                new: new_rate,
+            }),
+            min_value: Shift::changed(old.min_value, new.min_value),
+            max_value: Shift::changed(old.max_value, new.max_value),
+            distinct_count: Shift::changed(distinct_count(old_col), distinct_count(new_col)),
+            categories: _category_shifts(old_col, new_col),
+        }
+    }
+
+    /// Profiles both files and compares them column by column: data types,
+    /// null rates, value bounds, distinct counts and the share of each
+    /// category of text columns with few distinct values.
+    pub fn compare(old: &DataFrame, new: &DataFrame) -> ProfileComparison {
+        let old_set = ConstraintSet::new(old, "old");
+        let new_set = ConstraintSet::new(new, "new");
+        let old_names = old.get_column_names();
+        let new_names = new.get_column_names();
+        let mut columns = vec![];
+        for new_constraint in &new_set.set {
+            let Some(old_constraint) = old_set.set.iter().find(|c| c.name == new_constraint.name) else {
+                continue;
+            };
+            let (Ok(old_col), Ok(new_col)) = (old.column(&old_constraint.name), new.column(&new_constraint.name)) else {
+                continue;
+            };
+            let column = _compare_column(old_constraint, new_constraint, old_col, new_col);
+            if !column.is_empty() {
+                columns.push(column);
+            }
+        }
+        ProfileComparison {
+            rows: Shift {
+                old: old.height(),
+                new: new.height(),
+            },
+            added_columns: new_names.iter().filter(|n| !old_names.contains(n)).map(|n| n.to_string()).collect(),
+            removed_columns: old_names.iter().filter(|n| !new_names.contains(n)).map(|n| n.to_string()).collect(),
+            columns,
+        }
+    }
+
+    fn _bound(value: &Option<f64>) -> String {
+        value.map(|v| v.to_string()).unwrap_or_else(|| String::from("none"))
+    }
+
+    fn _percent(share: f64) -> String {
+        format!("{:.2}%", share * 100.0)
+    }
+
+    impl fmt::Display for ProfileComparison {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            writeln!(f, "Rows: {} -> {}", self.rows.old, self.rows.new)?;
+            if self.is_empty() {
+                return writeln!(f, "No differences");
+            }
+            for name in &self.added_columns {
+                writeln!(f, "+ {}: column added", name)?;
+            }
+            for name in &self.removed_columns {
+                writeln!(f, "- {}: column removed", name)?;
+            }
+            for column in &self.columns {
+                let name = &column.name;
+                if let Some(shift) = &column.data_type {
+                    writeln!(f, "~ {}: data_type {} -> {}", name, shift.old, shift.new)?;
+                }
+                if let Some(shift) = &column.null_rate {
+                    writeln!(f, "~ {}: null_rate {} -> {}", name, _percent(shift.old), _percent(shift.new))?;
+                }
+                if let Some(shift) = &column.min_value {
+                    writeln!(f, "~ {}: min_value {} -> {}", name, _bound(&shift.old), _bound(&shift.new))?;
+                }
+                if let Some(shift) = &column.max_value {
+                    writeln!(f, "~ {}: max_value {} -> {}", name, _bound(&shift.old), _bound(&shift.new))?;
+                }
+                if let Some(shift) = &column.distinct_count {
+                    writeln!(f, "~ {}: distinct_count {} -> {}", name, shift.old, shift.new)?;
+                }
+                for category in &column.categories {
+                    writeln!(
+                        f,
+                        "~ {}: share of {} {} -> {}",
+                        name,
+                        category.value,
+                        _percent(category.old_share),
+                        _percent(category.new_share)
+                    )?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compare::*;
+    use polars::prelude::*;
+
+    #[test]
+    fn compare_files() {
+        let old = df!(
+            "id" => &[1, 2, 3, 4],
+            "status" => &[Some("open"), Some("open"), Some("\"closed\""), Some("\"closed\"")],
+            "note" => &["a", "b", "c", "d"]
+        )
+        .unwrap();
+        let new = df!(
+            "id" => &[1.0, 2.0, 3.0, 8.0],
+            "status" => &[Some("open"), Some("open"), Some("null"), None],
+            "amount" => &[1, 2, 3, 4]
+        )
+        .unwrap();
+
+        assert!(compare(&old, &old).is_empty());
+
+        let comparison = compare(&old, &new);
+        assert_eq!(comparison.added_columns, vec!["amount"]);
+        assert_eq!(comparison.removed_columns, vec!["note"]);
+
+        let id = &comparison.columns[0];
+        assert_eq!(id.data_type.as_ref().map(|s| s.new.to_string()), Some(String::from("f64")));
+        assert_eq!(id.max_value, Some(Shift { old: Some(4.0), new: Some(8.0) }));
+        assert_eq!(id.null_rate, None);
+
+        let status = &comparison.columns[1];
+        assert_eq!(status.null_rate, Some(Shift { old: 0.0, new: 0.25 }));
+        assert_eq!(status.distinct_count, None);
+        let shares: Vec<(&str, f64, f64)> = status
+            .categories
+            .iter()
+            .map(|c| (c.value.as_str(), c.old_share, c.new_share))
+            .collect();
+        assert_eq!(shares, vec![("\"closed\"", 0.5, 0.0), ("open", 0.5, 2.0 / 3.0), ("null", 0.0, 1.0 / 3.0)]);
+        assert!(comparison.to_string().contains("~ status: share of open 50.00% -> 66.67%\n"));
+
+        let values: Vec<Option<i32>> = (0..200).map(Some).collect();
+        let old = df!("score" => &values).unwrap();
+        let mut values = values;
+        values[7] = None;
+        let new = df!("score" => &values).unwrap();
+        assert_eq!(compare(&old, &new).columns[0].null_rate, Some(Shift { old: 0.0, new: 0.005 }));
+    }
+}
@@ -1,8 +1,12 @@
 pub mod constraints {
+    pub use crate::semantic::semantic::SemanticType;
+    pub use crate::types::types::ColumnType;
+    use chrono::{NaiveDate, NaiveDateTime};
     use polars::export::num::ToPrimitive;
     use polars::prelude::*;
     use serde::{Deserialize, Serialize};
-    use std::collections::HashSet;
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    use sha2::{Digest, Sha256};
     use std::fmt;
     use std::str::FromStr;
     use std::io::Write;
@@ -11,10 +15,531 @@ pub mod constraints {
     use std::io::prelude::*;
 
 
+    /// How `min_value`/`max_value` are inferred from the reference data.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(tag = "strategy", rename_all = "snake_case")]
+    pub enum BoundsStrategy {
+        /// The absolute extremes of the column.
+        MinMax,
+        /// The `lower` and `upper` quantiles, widened on each side by `margin`
+        /// times the distance between them. Built with
+        /// `BoundsStrategy::quantile`, as quantiles outside `0..=1` give no
+        /// bounds.
+        Quantile { lower: f64, upper: f64, margin: f64 },
+    }
+
+    impl BoundsStrategy {
+        /// Quantile bounds, checking that `0 <= lower <= upper <= 1`.
+        pub fn quantile(lower: f64, upper: f64, margin: f64) -> Result<BoundsStrategy, String> {
+            if !(0.0..=1.0).contains(&lower) || !(0.0..=1.0).contains(&upper) {
+                return Err(format!("Quantiles must be between 0 and 1, got {} and {}", lower, upper));
+            }
+            if lower > upper {
+                return Err(format!("Lower quantile {} is above upper quantile {}", lower, upper));
+            }
+            Ok(BoundsStrategy::Quantile { lower, upper, margin })
+        }
+    }
+
+    /// Distribution of a numeric column in the reference data.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct ColumnStats {
+        pub mean: f64,
+        pub std_dev: f64,
+        pub q1: f64,
+        pub median: f64,
+        pub q3: f64,
+    }
+
+    /// How serious a failed check is. Only errors make a validation fail.
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Severity {
+        #[default]
+        Error,
+        Warning,
+        Info,
+    }
+
+    impl FromStr for Severity {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Severity, String> {
+            serde_json::from_value(serde_json::Value::from(s.to_lowercase()))
+                .map_err(|_| format!("Unknown severity: {}", s))
+        }
+    }
+
+    impl fmt::Display for Severity {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = serde_json::to_value(self).unwrap_or_default();
+            write!(f, "{}", name.as_str().unwrap_or_default())
+        }
+    }
+
+    /// How the columns of the data are matched to constraint names and
+    /// aliases.
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum NameMatching {
+        /// Column names must equal the name or an alias.
+        #[default]
+        Exact,
+        /// Case and whitespace are ignored when no column matches exactly,
+        /// so ` Residence_Type` matches `residence_type`.
+        Insensitive,
+    }
+
+    impl FromStr for NameMatching {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<NameMatching, String> {
+            serde_json::from_value(serde_json::Value::from(s.to_lowercase()))
+                .map_err(|_| format!("Unknown name matching: {}", s))
+        }
+    }
+
+    /// Options controlling how constraints are inferred from a DataFrame.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct InferenceOptions {
+        pub bounds: BoundsStrategy,
+        /// Share of non-null values of a string column that must parse as a
+        /// type for it to be suggested.
+        pub type_threshold: f64,
+        /// Share of non-null values of a string column that must match a
+        /// semantic type for it to be detected.
+        pub semantic_threshold: f64,
+        /// Standard deviations from the mean beyond which values are outliers.
+        pub z_score: Option<f64>,
+        /// Multiple of the interquartile range outside which values are outliers.
+        pub iqr_factor: Option<f64>,
+        /// Relative width of the aggregate ranges around the profiled values.
+        /// Aggregates are only profiled when this is set.
+        pub aggregate_tolerance: Option<f64>,
+    }
+
+    impl Default for InferenceOptions {
+        fn default() -> InferenceOptions {
+            InferenceOptions {
+                bounds: BoundsStrategy::MinMax,
+                type_threshold: 0.95,
+                semantic_threshold: 0.95,
+                z_score: None,
+                iqr_factor: None,
+                aggregate_tolerance: None,
+            }
+        }
+    }
+
+    /// Inclusive range of accepted values of a column aggregate.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct AggregateRange {
+        pub min: f64,
+        pub max: f64,
+    }
+
+    impl AggregateRange {
+        /// Range of `value` widened by `tolerance` times its magnitude.
+        pub fn around(value: f64, tolerance: f64) -> AggregateRange {
+            let margin = value.abs() * tolerance;
+            AggregateRange {
+                min: value - margin,
+                max: value + margin,
+            }
+        }
+
+        pub fn contains(&self, value: f64) -> bool {
+            value >= self.min && value <= self.max
+        }
+    }
+
+    impl FromStr for AggregateRange {
+        type Err = String;
+
+        /// Parses `MIN,MAX`.
+        fn from_str(s: &str) -> Result<AggregateRange, String> {
+            let bounds: Vec<f64> = s
+                .split(',')
+                .map(|v| f64::from_str(v.trim()).map_err(|_| format!("Invalid range: {}", s)))
+                .collect::<Result<_, _>>()?;
+            match bounds[..] {
+                [min, max] if min <= max => Ok(AggregateRange { min, max }),
+                _ => Err(format!("Invalid range: {}", s)),
+            }
+        }
+    }
+
+    /// Names of the column aggregates that can be constrained.
+    pub const AGGREGATES: [&str; 6] = ["mean", "median", "sum", "std_dev", "distinct_count", "null_count"];
+    /// Aggregates that don't depend on the number of rows, and so are
+    /// profiled. Ranges of the sum and counts are only set by hand.
+    pub const PROFILED_AGGREGATES: [&str; 3] = ["mean", "median", "std_dev"];
+
+    /// Value of an aggregate of a column. Mean, median, sum and standard
+    /// deviation are only defined for numeric columns; counts skip nulls.
+    pub fn aggregate(col: &Series, name: &str) -> Option<f64> {
+        match name {
+            "distinct_count" => col.drop_nulls().n_unique().ok()?.to_f64(),
+            "null_count" => col.null_count().to_f64(),
+            _ => {
+                let values = numeric_values(col)?;
+                if values.is_empty() {
+                    return None;
+                }
+                let count = values.len() as f64;
+                let sum: f64 = values.iter().sum();
+                match name {
+                    "mean" => Some(sum / count),
+                    "sum" => Some(sum),
+                    "std_dev" => {
+                        let mean = sum / count;
+                        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (count - 1.0).max(1.0);
+                        Some(variance.sqrt())
+                    }
+                    "median" => col
+                        .cast(&DataType::Float64)
+                        .ok()?
+                        .f64()
+                        .ok()?
+                        .quantile(0.5, QuantileInterpolOptions::Linear)
+                        .ok()?,
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Repairs applied by the `fix` command to values failing a constraint.
+    #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+    pub struct Repairs {
+        /// Value replacing nulls in columns that aren't nullable.
+        pub fill_null: Option<String>,
+        /// Strip leading and trailing whitespace.
+        #[serde(default)]
+        pub trim: bool,
+        /// Replace values matching a `value_range` member except for case
+        /// and surrounding whitespace with that member.
+        #[serde(default)]
+        pub map_case: bool,
+        /// Cut strings longer than `max_length`.
+        #[serde(default)]
+        pub truncate: bool,
+        /// Move values below `min_value` or above `max_value` to the bound.
+        #[serde(default)]
+        pub clamp: bool,
+    }
+
+    /// Letter case that the values of a text column must be written in.
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Case {
+        Lower,
+        Upper,
+        /// Every word starts with an uppercase letter followed by lowercase ones.
+        Title,
+    }
+
+    impl Case {
+        pub fn matches(&self, value: &str) -> bool {
+            match self {
+                Case::Lower => value == value.to_lowercase(),
+                Case::Upper => value == value.to_uppercase(),
+                Case::Title => value.split_whitespace().all(|word| {
+                    let mut chars = word.chars();
+                    chars.next().is_none_or(|c| !c.is_lowercase())
+                        && chars.all(|c| !c.is_uppercase())
+                }),
+            }
+        }
+    }
+
+    impl FromStr for Case {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Case, String> {
+            serde_json::from_value(serde_json::Value::from(s.to_lowercase()))
+                .map_err(|_| format!("Unknown case: {}", s))
+        }
+    }
+
+    /// Pattern matching text made only of characters in `class`, a regex
+    /// character class body such as `\p{L}\p{N} -`. `None` when the class
+    /// is invalid. Compiled once per column and matched against each value.
+    pub fn allowed_chars_pattern(class: &str) -> Option<regex::Regex> {
+        regex::Regex::new(&format!("^[{}]*$", class)).ok()
+    }
+
+    /// Order that the values of a column must follow from row to row.
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Monotonic {
+        Increasing,
+        StrictlyIncreasing,
+        Decreasing,
+        StrictlyDecreasing,
+    }
+
+    impl Monotonic {
+        /// Whether `next` may follow `previous`.
+        pub fn allows<T: PartialOrd>(&self, previous: &T, next: &T) -> bool {
+            match self {
+                Monotonic::Increasing => next >= previous,
+                Monotonic::StrictlyIncreasing => next > previous,
+                Monotonic::Decreasing => next <= previous,
+                Monotonic::StrictlyDecreasing => next < previous,
+            }
+        }
+
+        /// Rows whose value breaks the order with the previous non-null
+        /// value, as zero-based row indexes. Numeric columns are compared as
+        /// numbers, text that all parses with one date or datetime format as
+        /// timestamps, so `31/01/2024` comes before `01/02/2024`, and other
+        /// text as text.
+        pub fn breaks(&self, col: &Series) -> Vec<usize> {
+            fn _breaks<T: PartialOrd>(monotonic: &Monotonic, values: Vec<Option<T>>) -> Vec<usize> {
+                let mut previous: Option<T> = None;
+                let mut rows = vec![];
+                for (row, value) in values.into_iter().enumerate() {
+                    let Some(value) = value else { continue };
+                    if previous.as_ref().is_some_and(|p| !monotonic.allows(p, &value)) {
+                        rows.push(row);
+                    }
+                    previous = Some(value);
+                }
+                rows
+            }
+
+            if col.dtype().is_numeric() {
+                match col.cast(&DataType::Float64) {
+                    Ok(col) => _breaks(self, col.f64().map(|c| c.into_iter().collect()).unwrap_or_default()),
+                    Err(_) => vec![],
+                }
+            } else {
+                let Ok(col) = col.cast(&DataType::Utf8) else { return vec![] };
+                let texts: Vec<Option<&str>> = col.utf8().map(|c| c.into_iter().collect()).unwrap_or_default();
+                match _parse_timestamps(&texts) {
+                    Some(timestamps) => _breaks(self, timestamps),
+                    None => _breaks(self, texts),
+                }
+            }
+        }
+    }
+
+    /// Text values as timestamps, when every non-null value parses with
+    /// the same date or datetime format.
+    fn _parse_timestamps(values: &[Option<&str>]) -> Option<Vec<Option<NaiveDateTime>>> {
+        let dates = DATE_FORMATS.iter().map(|format| (format, true));
+        let datetimes = DATETIME_FORMATS.iter().map(|format| (format, false));
+        dates.chain(datetimes).find_map(|(format, is_date)| {
+            let parse = |value: &str| -> Option<NaiveDateTime> {
+                if is_date {
+                    NaiveDate::parse_from_str(value.trim(), format).ok()?.and_hms_opt(0, 0, 0)
+                } else {
+                    NaiveDateTime::parse_from_str(value.trim(), format).ok()
+                }
+            };
+            values
+                .iter()
+                .map(|value| match value {
+                    Some(value) => parse(value).map(Some),
+                    None => Some(None),
+                })
+                .collect()
+        })
+    }
+
+    impl FromStr for Monotonic {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Monotonic, String> {
+            serde_json::from_value(serde_json::Value::from(s.to_lowercase()))
+                .map_err(|_| format!("Unknown order: {}", s))
+        }
+    }
+
+    /// Values missing from an integer sequence, as `(first, last)` ranges
+    /// between the smallest and largest value. `None` for columns that
+    /// aren't whole numbers.
+    pub fn sequence_gaps(col: &Series) -> Option<Vec<(i64, i64)>> {
+        let values = numeric_values(col)?;
+        if values.iter().any(|v| v.fract() != 0.0) {
+            return None;
+        }
+        let mut values: Vec<i64> = values.into_iter().map(|v| v as i64).collect();
+        values.sort_unstable();
+        values.dedup();
+        Some(
+            values
+                .windows(2)
+                .filter(|w| w[1] - w[0] > 1)
+                .map(|w| (w[0] + 1, w[1] - 1))
+                .collect(),
+        )
+    }
+
+    /// Expected ranges of column aggregates, catching batches where every
+    /// row is valid but the column as a whole has shifted.
+    #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+    pub struct Aggregates {
+        pub mean: Option<AggregateRange>,
+        pub median: Option<AggregateRange>,
+        pub sum: Option<AggregateRange>,
+        pub std_dev: Option<AggregateRange>,
+        pub distinct_count: Option<AggregateRange>,
+        pub null_count: Option<AggregateRange>,
+    }
+
+    impl Aggregates {
+        pub fn range(&self, name: &str) -> Option<&AggregateRange> {
+            match name {
+                "mean" => self.mean.as_ref(),
+                "median" => self.median.as_ref(),
+                "sum" => self.sum.as_ref(),
+                "std_dev" => self.std_dev.as_ref(),
+                "distinct_count" => self.distinct_count.as_ref(),
+                "null_count" => self.null_count.as_ref(),
+                _ => None,
+            }
+        }
+
+        /// Sets the range of an aggregate, returning false for unknown names.
+        pub fn set_range(&mut self, name: &str, range: Option<AggregateRange>) -> bool {
+            let slot = match name {
+                "mean" => &mut self.mean,
+                "median" => &mut self.median,
+                "sum" => &mut self.sum,
+                "std_dev" => &mut self.std_dev,
+                "distinct_count" => &mut self.distinct_count,
+                "null_count" => &mut self.null_count,
+                _ => return false,
+            };
+            *slot = range;
+            true
+        }
+
+        pub fn new(col: &Series, tolerance: f64) -> Aggregates {
+            let mut aggregates = Aggregates::default();
+            for name in PROFILED_AGGREGATES {
+                let range = aggregate(col, name).map(|v| AggregateRange::around(v, tolerance));
+                aggregates.set_range(name, range);
+            }
+            aggregates
+        }
+    }
+
+    const BOOL_VALUES: [&str; 10] = ["true", "false", "t", "f", "yes", "no", "y", "n", "1", "0"];
+    const DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%Y/%m/%d", "%d/%m/%Y", "%m/%d/%Y"];
+    const DATETIME_FORMATS: [&str; 3] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"];
+
+    /// Types that string columns are checked against, in order of preference.
+    const SUGGESTED_TYPES: [ColumnType; 4] = [
+        ColumnType::Bool,
+        ColumnType::Int64,
+        ColumnType::Float64,
+        ColumnType::Date,
+    ];
+
+    /// Total digits and digits after the decimal point of a number written
+    /// as plain decimal text, e.g. `-0012.50` is `(4, 2)`.
+    pub fn decimal_digits(value: &str) -> Option<(u32, u32)> {
+        let value = value.trim().trim_start_matches(['-', '+']);
+        let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+        if integer.is_empty() && fraction.is_empty()
+            || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let integer_digits = integer.trim_start_matches('0').len() as u32;
+        let scale = fraction.len() as u32;
+        Some((integer_digits + scale, scale))
+    }
+
+    /// Numeric values of a column as decimal text. Strings are kept as
+    /// written; floats use their shortest round-trip representation, so
+    /// `0.3` read from a file stays `0.3`, but trailing zeros like those of
+    /// `12.50` are lost. Profiling a file reads its float columns as text to
+    /// keep them.
+    pub fn decimal_texts(col: &Series) -> Option<Vec<Option<String>>> {
+        match col.dtype() {
+            DataType::Utf8 => Some(
+                col.utf8()
+                    .ok()?
+                    .into_iter()
+                    .map(|v| v.map(String::from))
+                    .collect(),
+            ),
+            DataType::Float32 | DataType::Float64 => Some(
+                col.cast(&DataType::Float64)
+                    .ok()?
+                    .f64()
+                    .ok()?
+                    .into_iter()
+                    .map(|v| v.filter(|v| v.is_finite()).map(|v| v.to_string()))
+                    .collect(),
+            ),
+            dtype if dtype.is_numeric() => Some(
+                col.cast(&DataType::Int64)
+                    .ok()?
+                    .i64()
+                    .ok()?
+                    .into_iter()
+                    .map(|v| v.map(|v| v.to_string()))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Non-null values of a numeric column as floats.
+    pub fn numeric_values(col: &Series) -> Option<Vec<f64>> {
+        if !col.dtype().is_numeric() {
+            return None;
+        }
+        Some(col.cast(&DataType::Float64).ok()?.f64().ok()?.into_iter().flatten().collect())
+    }
+
+    /// Whether `value` is a whole multiple of `step`, allowing for float error.
+    pub fn is_multiple_of(value: f64, step: f64) -> bool {
+        let nearest = (value / step).round() * step;
+        (nearest - value).abs() <= 1e-9 * value.abs().max(1.0)
+    }
+
+    fn _gcd(a: u64, b: u64) -> u64 {
+        if b == 0 {
+            a
+        } else {
+            _gcd(b, a % b)
+        }
+    }
+
+    /// Whether a raw string value can be read as the given data type.
+    pub fn parses_as(value: &str, data_type: &ColumnType) -> bool {
+        let value = value.trim();
+        match data_type {
+            ColumnType::Bool => BOOL_VALUES.contains(&value.to_lowercase().as_str()),
+            t if t.is_integer() => value.parse::<i64>().is_ok(),
+            t if t.is_numeric() => value.parse::<f64>().is_ok(),
+            ColumnType::Date | ColumnType::Datetime => {
+                DATE_FORMATS
+                    .iter()
+                    .any(|f| NaiveDate::parse_from_str(value, f).is_ok())
+                    || DATETIME_FORMATS
+                        .iter()
+                        .any(|f| NaiveDateTime::parse_from_str(value, f).is_ok())
+            }
+            _ => true,
+        }
+    }
+
     #[derive(Serialize, Deserialize, Debug, PartialEq)]
     pub struct Constraint {
         pub name: String,
-        pub data_type: String,
+        /// Free-text description of the column.
+        pub description: Option<String>,
+        /// Other names the column may have in the data.
+        #[serde(default)]
+        pub aliases: Vec<String>,
+        pub data_type: ColumnType,
         pub nullable: bool,
         pub unique: bool,
         pub min_length: Option<u32>,
@@ -22,9 +547,54 @@ pub mod constraints {
         pub min_value: Option<f64>,
         pub max_value: Option<f64>,
         pub value_range: Option<String>,
+        pub bounds_strategy: Option<BoundsStrategy>,
+        pub suggested_type: Option<ColumnType>,
+        pub semantic_type: Option<SemanticType>,
+        pub precision: Option<u32>,
+        pub scale: Option<u32>,
+        #[serde(default)]
+        pub integer_only: bool,
+        #[serde(default)]
+        pub positive: bool,
+        #[serde(default)]
+        pub non_negative: bool,
+        pub multiple_of: Option<f64>,
+        pub stats: Option<ColumnStats>,
+        pub z_score: Option<f64>,
+        pub iqr_factor: Option<f64>,
+        #[serde(default)]
+        pub aggregates: Aggregates,
+        pub monotonic: Option<Monotonic>,
+        /// Whole-number columns whose values must form a sequence without
+        /// missing numbers, like invoice numbers.
+        #[serde(default)]
+        pub no_gaps: bool,
+        /// Text values have no leading or trailing whitespace.
+        #[serde(default)]
+        pub trimmed: bool,
+        pub case: Option<Case>,
+        #[serde(default)]
+        pub ascii_only: bool,
+        /// Regex character class that every character must belong to.
+        pub allowed_chars: Option<String>,
+        #[serde(default)]
+        pub no_control_chars: bool,
+        #[serde(default)]
+        pub repairs: Repairs,
+        /// Severity of the checks of this column.
+        #[serde(default)]
+        pub severity: Severity,
+        /// Severity of single checks by validation check name, e.g.
+        /// `value_range`, overriding `severity`.
+        #[serde(default)]
+        pub severities: BTreeMap<String, Severity>,
     }
 
     impl Constraint {
+        pub fn severity_of(&self, check: &str) -> Severity {
+            self.severities.get(check).copied().unwrap_or(self.severity)
+        }
+
         fn _get_value_range(data: &DataFrame, colname: &str) -> Option<String> {
             let col = data.column(colname);
 
@@ -34,7 +604,7 @@ pub mod constraints {
             };
 
             if dtype != "str" {
-                return None;
+                None
             } else {
                 let mut unique_values = HashSet::new();
                 let series = col.cloned().unwrap_or_default();
@@ -42,16 +612,14 @@ pub mod constraints {
                     unique_values.insert(value.to_string());
                 }
                 let unique_vec = unique_values.into_iter().collect::<Vec<String>>();
-                let unique_str = Some(unique_vec.join(", ").replace(['\\', '"'], ""));
-                unique_str
+                Some(unique_vec.join(", ").replace(['\\', '"'], ""))
             }
         }
 
-        fn _get_data_type(data: &DataFrame, colname: &str) -> String {
+        fn _get_data_type(data: &DataFrame, colname: &str) -> ColumnType {
             data.column(colname)
-                .map(|s| s.dtype())
-                .unwrap_or(&DataType::Null)
-                .clone().to_string()
+                .map(|s| ColumnType::from(s.dtype()))
+                .unwrap_or(ColumnType::Null)
         }
 
         fn _is_nullable(data: &DataFrame, colname: &str) -> bool {
@@ -78,26 +646,305 @@ pub mod constraints {
                 .and_then(|s| s.utf8().map(|s| s.str_lengths().max()).unwrap_or_default())
         }
 
-        fn _get_min_value(data: &DataFrame, colname: &str) -> Option<f64> {
-            data.column(colname).ok().and_then(|s| s.min())
+        fn _get_quantile(data: &DataFrame, colname: &str, quantile: f64) -> Option<f64> {
+            let col = data.column(colname).ok()?;
+            if !col.dtype().is_numeric() {
+                return None;
+            }
+            col.cast(&DataType::Float64)
+                .ok()?
+                .f64()
+                .ok()?
+                .quantile(quantile, QuantileInterpolOptions::Linear)
+                .ok()?
+        }
+
+        fn _get_min_value(data: &DataFrame, colname: &str, strategy: &BoundsStrategy) -> Option<f64> {
+            match strategy {
+                BoundsStrategy::MinMax => data.column(colname).ok().and_then(|s| s.min()),
+                BoundsStrategy::Quantile { lower, upper, margin } => {
+                    let low = Self::_get_quantile(data, colname, *lower)?;
+                    let high = Self::_get_quantile(data, colname, *upper)?;
+                    Some(low - margin * (high - low))
+                }
+            }
+        }
+
+        fn _get_max_value(data: &DataFrame, colname: &str, strategy: &BoundsStrategy) -> Option<f64> {
+            match strategy {
+                BoundsStrategy::MinMax => data.column(colname).ok().and_then(|s| s.max()),
+                BoundsStrategy::Quantile { lower, upper, margin } => {
+                    let low = Self::_get_quantile(data, colname, *lower)?;
+                    let high = Self::_get_quantile(data, colname, *upper)?;
+                    Some(high + margin * (high - low))
+                }
+            }
+        }
+
+        /// Suggests a type for string columns where most values are numbers,
+        /// booleans or dates but polars fell back to `str`.
+        fn _get_suggested_type(data: &DataFrame, colname: &str, threshold: f64) -> Option<ColumnType> {
+            let col = data.column(colname).ok()?;
+            let values: Vec<&str> = col.utf8().ok()?.into_iter().flatten().collect();
+            if values.is_empty() {
+                return None;
+            }
+            SUGGESTED_TYPES
+                .iter()
+                .find(|t| {
+                    let parsed = values.iter().filter(|v| parses_as(v, t)).count();
+                    parsed as f64 / values.len() as f64 >= threshold
+                })
+                .cloned()
+        }
+
+        fn _get_semantic_type(data: &DataFrame, colname: &str, threshold: f64) -> Option<SemanticType> {
+            let col = data.column(colname).ok()?;
+            let values: Vec<&str> = col.utf8().ok()?.into_iter().flatten().collect();
+            SemanticType::detect(&values, threshold)
+        }
+
+        /// Largest total digits and decimal places of a decimal column, read
+        /// from its text. Only float columns and text columns that look like
+        /// floats are profiled.
+        fn _get_precision_scale(data: &DataFrame, colname: &str, suggested_type: &Option<ColumnType>) -> Option<(u32, u32)> {
+            let col = data.column(colname).ok()?;
+            let is_decimal = match col.dtype() {
+                DataType::Float32 | DataType::Float64 => true,
+                DataType::Utf8 => suggested_type.as_ref().is_some_and(|t| t.is_float()),
+                _ => false,
+            };
+            if !is_decimal {
+                return None;
+            }
+            Self::_max_decimal_digits(col)
+        }
+
+        /// Largest total digits and decimal places of the values as written.
+        fn _max_decimal_digits(col: &Series) -> Option<(u32, u32)> {
+            decimal_texts(col)?
+                .iter()
+                .flatten()
+                .filter_map(|v| decimal_digits(v))
+                .reduce(|(p1, s1), (p2, s2)| (p1.max(p2), s1.max(s2)))
+        }
+
+        fn _numeric_values(data: &DataFrame, colname: &str) -> Vec<f64> {
+            data.column(colname)
+                .ok()
+                .and_then(numeric_values)
+                .unwrap_or_default()
+        }
+
+        fn _is_integer_only(values: &[f64]) -> bool {
+            !values.is_empty() && values.iter().all(|v| v.fract() == 0.0)
+        }
+
+        fn _is_positive(values: &[f64]) -> bool {
+            !values.is_empty() && values.iter().all(|v| *v > 0.0)
+        }
+
+        fn _is_non_negative(values: &[f64]) -> bool {
+            !values.is_empty() && values.iter().all(|v| *v >= 0.0)
+        }
+
+        /// Greatest common divisor of whole-number columns, when it's above 1.
+        fn _get_multiple_of(values: &[f64]) -> Option<f64> {
+            if !Self::_is_integer_only(values) {
+                return None;
+            }
+            let gcd = values
+                .iter()
+                .map(|v| v.abs() as u64)
+                .fold(0, _gcd);
+            if gcd > 1 {
+                Some(gcd as f64)
+            } else {
+                None
+            }
+        }
+
+        /// Order followed by numeric and date columns of the reference data,
+        /// strict when possible. Columns with fewer than two distinct values
+        /// have no meaningful order.
+        fn _get_monotonic(data: &DataFrame, colname: &str, suggested_type: &Option<ColumnType>) -> Option<Monotonic> {
+            let col = data.column(colname).ok()?;
+            let is_ordered = col.dtype().is_numeric()
+                || ColumnType::from(col.dtype()).is_temporal()
+                || suggested_type.as_ref().is_some_and(|t| t.is_temporal());
+            if !is_ordered || col.drop_nulls().n_unique().ok()? < 2 {
+                return None;
+            }
+            [
+                Monotonic::StrictlyIncreasing,
+                Monotonic::StrictlyDecreasing,
+                Monotonic::Increasing,
+                Monotonic::Decreasing,
+            ]
+            .into_iter()
+            .find(|m| m.breaks(col).is_empty())
         }
 
-        fn _get_max_value(data: &DataFrame, colname: &str) -> Option<f64> {
-            data.column(colname).ok().and_then(|s| s.max())
+        /// Unique whole-number columns without missing numbers look like
+        /// sequences, e.g. invoice numbers.
+        fn _is_gap_free(data: &DataFrame, colname: &str) -> bool {
+            data.column(colname)
+                .ok()
+                .filter(|col| col.len() > 1 && col.is_unique().map(|u| u.all()).unwrap_or_default())
+                .and_then(sequence_gaps)
+                .is_some_and(|gaps| gaps.is_empty())
+        }
+
+        fn _string_values(data: &DataFrame, colname: &str) -> Vec<String> {
+            data.column(colname)
+                .ok()
+                .and_then(|col| col.utf8().ok().map(|s| s.into_iter().flatten().map(String::from).collect()))
+                .unwrap_or_default()
+        }
+
+        /// Case shared by all values, if they have any letters at all.
+        fn _get_case(values: &[String]) -> Option<Case> {
+            if !values.iter().any(|v| v.chars().any(char::is_alphabetic)) {
+                return None;
+            }
+            [Case::Lower, Case::Upper, Case::Title]
+                .into_iter()
+                .find(|case| values.iter().all(|v| case.matches(v)))
+        }
+
+        fn _get_stats(data: &DataFrame, colname: &str, values: &[f64]) -> Option<ColumnStats> {
+            if values.is_empty() {
+                return None;
+            }
+            let count = values.len() as f64;
+            let mean = values.iter().sum::<f64>() / count;
+            let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (count - 1.0).max(1.0);
+            Some(ColumnStats {
+                mean,
+                std_dev: variance.sqrt(),
+                q1: Self::_get_quantile(data, colname, 0.25)?,
+                median: Self::_get_quantile(data, colname, 0.5)?,
+                q3: Self::_get_quantile(data, colname, 0.75)?,
+            })
+        }
+
+        /// A constraint that accepts anything, for building constraints by
+        /// hand: any data type, nullable, and no other checks.
+        pub fn named(name: &str) -> Constraint {
+            Constraint {
+                name: String::from(name),
+                description: None,
+                aliases: vec![],
+                data_type: ColumnType::Any,
+                nullable: true,
+                unique: false,
+                min_length: None,
+                max_length: None,
+                min_value: None,
+                max_value: None,
+                value_range: None,
+                bounds_strategy: None,
+                suggested_type: None,
+                semantic_type: None,
+                precision: None,
+                scale: None,
+                integer_only: false,
+                positive: false,
+                non_negative: false,
+                multiple_of: None,
+                stats: None,
+                z_score: None,
+                iqr_factor: None,
+                aggregates: Aggregates::default(),
+                monotonic: None,
+                no_gaps: false,
+                trimmed: false,
+                case: None,
+                ascii_only: false,
+                allowed_chars: None,
+                no_control_chars: false,
+                repairs: Repairs::default(),
+                severity: Severity::default(),
+                severities: BTreeMap::new(),
+            }
         }
 
         pub fn new(data: &DataFrame, colname: &str) -> Constraint {
-            return Constraint {
+            Self::infer(data, colname, &InferenceOptions::default())
+        }
+
+        pub fn infer(data: &DataFrame, colname: &str, options: &InferenceOptions) -> Constraint {
+            let min_value = Self::_get_min_value(data, colname, &options.bounds);
+            let max_value = Self::_get_max_value(data, colname, &options.bounds);
+            let bounds_strategy = min_value.and(Some(options.bounds.clone()));
+            let suggested_type = Self::_get_suggested_type(data, colname, options.type_threshold);
+            let precision_scale = Self::_get_precision_scale(data, colname, &suggested_type);
+            let monotonic = Self::_get_monotonic(data, colname, &suggested_type);
+            let strings = Self::_string_values(data, colname);
+            let has_strings = !strings.is_empty();
+            let values = Self::_numeric_values(data, colname);
+            let stats = Self::_get_stats(data, colname, &values);
+            let no_gaps = Self::_is_gap_free(data, colname);
+            let trimmed = has_strings && strings.iter().all(|v| v.trim() == v);
+            let case = Self::_get_case(&strings);
+            let ascii_only = has_strings && strings.iter().all(|v| v.is_ascii());
+            let no_control_chars = has_strings && !strings.iter().any(|v| v.chars().any(char::is_control));
+            // An order or a text style seen in one file may be chance, e.g. a
+            // sorted sample or no accented names yet, so breaking it is only a
+            // warning until set otherwise.
+            let inferred_warnings = [
+                ("monotonic", monotonic.is_some()),
+                ("gaps", no_gaps),
+                ("gaps_not_applicable", no_gaps),
+                ("trimmed", trimmed),
+                ("case", case.is_some()),
+                ("ascii_only", ascii_only),
+                ("control_chars", no_control_chars),
+            ];
+            let severities: BTreeMap<String, Severity> = inferred_warnings
+                .into_iter()
+                .filter(|(_, inferred)| *inferred)
+                .map(|(check, _)| (String::from(check), Severity::Warning))
+                .collect();
+            Constraint {
                 name: String::from(colname),
+                description: None,
+                aliases: vec![],
                 data_type: Self::_get_data_type(data, colname),
                 nullable: Self::_is_nullable(data, colname),
                 unique: Self::_is_unique(data, colname),
                 min_length: Self::_get_min_length(data, colname),
                 max_length: Self::_get_max_length(data, colname),
-                min_value: Self::_get_min_value(data, colname),
-                max_value: Self::_get_max_value(data, colname),
-                value_range: Self::_get_value_range(data, &colname),
-            };
+                min_value,
+                max_value,
+                value_range: Self::_get_value_range(data, colname),
+                bounds_strategy,
+                suggested_type,
+                semantic_type: Self::_get_semantic_type(data, colname, options.semantic_threshold),
+                precision: precision_scale.map(|(precision, _)| precision),
+                scale: precision_scale.map(|(_, scale)| scale),
+                integer_only: Self::_is_integer_only(&values),
+                positive: Self::_is_positive(&values),
+                non_negative: Self::_is_non_negative(&values),
+                multiple_of: Self::_get_multiple_of(&values),
+                z_score: stats.as_ref().and(options.z_score),
+                iqr_factor: stats.as_ref().and(options.iqr_factor),
+                stats,
+                aggregates: match (data.column(colname), options.aggregate_tolerance) {
+                    (Ok(col), Some(tolerance)) => Aggregates::new(col, tolerance),
+                    _ => Aggregates::default(),
+                },
+                monotonic,
+                no_gaps,
+                trimmed,
+                case,
+                ascii_only,
+                allowed_chars: None,
+                no_control_chars,
+                repairs: Repairs::default(),
+                severity: Severity::default(),
+                severities,
+            }
         }
     }
 
@@ -111,10 +958,10 @@ pub mod constraints {
             } else {
                 &range_string
             };
-            write!(f, "+{:<}+\n", "-".repeat(name_length + 178)).unwrap_or_default();
-            write!(
+            writeln!(f, "+{:<}+", "-".repeat(name_length + 178)).unwrap_or_default();
+            writeln!(
                 f,
-                "|{:<width1$}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<width2$}|\n",
+                "|{:<width1$}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<width2$}|",
                 "Name",
                 "Data Type",
                 "Nullable",
@@ -127,10 +974,10 @@ pub mod constraints {
                 width1 = name_length,
                 width2 = 60
             ).unwrap_or_default();
-            write!(f, "+{:<}+\n", "-".repeat(name_length + 178)).unwrap_or_default();
-            write!(
+            writeln!(f, "+{:<}+", "-".repeat(name_length + 178)).unwrap_or_default();
+            writeln!(
                 f,
-                "|{:<width1$}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<60}|\n",
+                "|{:<width1$}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<60}|",
                 self.name,
                 self.data_type.to_string(),
                 self.nullable,
@@ -142,39 +989,417 @@ pub mod constraints {
                 trimmed_range, 
                 width1 = name_length,
                 ).unwrap_or_default();
-            write!(f, "+{:<}+\n", "-".repeat(name_length + 178))
+            writeln!(f, "+{:<}+", "-".repeat(name_length + 178))
+        }
+    }
+
+    /// Serialization format of a constraint file.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum FileFormat {
+        Json,
+        Yaml,
+        Toml,
+    }
+
+    impl FileFormat {
+        /// Picks the format from the file extension. Anything unknown is JSON.
+        pub fn from_path(filepath: &str) -> FileFormat {
+            let ext = Path::new(filepath)
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_lowercase());
+            match ext.as_deref() {
+                Some("yaml") | Some("yml") => FileFormat::Yaml,
+                Some("toml") => FileFormat::Toml,
+                _ => FileFormat::Json,
+            }
+        }
+    }
+
+    /// Where the expected row count of a file comes from.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum RowCountSource {
+        /// A control file whose last number is the row count.
+        ControlFile(String),
+        /// The last line of the data file itself, e.g. `TRAILER,4981`. That
+        /// line is not counted as a data row.
+        Trailer,
+    }
+
+    /// Settings of the duplicate full-row check.
+    #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+    pub struct DuplicateRows {
+        /// Columns compared; all columns when empty.
+        #[serde(default)]
+        pub columns: Vec<String>,
+        #[serde(default)]
+        pub ignore_whitespace: bool,
+        #[serde(default)]
+        pub ignore_case: bool,
+    }
+
+    impl DuplicateRows {
+        fn _normalize(&self, value: &str) -> String {
+            let value = if self.ignore_whitespace {
+                value.split_whitespace().collect::<Vec<&str>>().join(" ")
+            } else {
+                String::from(value)
+            };
+            if self.ignore_case {
+                value.to_lowercase()
+            } else {
+                value
+            }
+        }
+
+        /// Pairs of (row, first row with the same values) for every repeated
+        /// row, as zero-based row indexes. Fails when a configured column is
+        /// missing from the data.
+        pub fn find(&self, data: &DataFrame) -> Result<Vec<(usize, usize)>, String> {
+            let names: Vec<&str> = if self.columns.is_empty() {
+                data.get_column_names()
+            } else {
+                self.columns.iter().map(|c| c.as_str()).collect()
+            };
+            let columns: Vec<Vec<Option<String>>> = names
+                .iter()
+                .map(|name| {
+                    let col = data
+                        .column(name)
+                        .and_then(|col| col.cast(&DataType::Utf8))
+                        .map_err(|_| format!("Column {} of duplicate_rows not found", name))?;
+                    let values = col.utf8().map_err(|e| e.to_string())?;
+                    Ok(values.into_iter().map(|v| v.map(|v| self._normalize(v))).collect())
+                })
+                .collect::<Result<_, String>>()?;
+
+            let mut first_rows: HashMap<Vec<Option<&String>>, usize> = HashMap::new();
+            let mut duplicates = vec![];
+            for row in 0..data.height() {
+                let key: Vec<Option<&String>> = columns.iter().map(|c| c[row].as_ref()).collect();
+                match first_rows.get(&key) {
+                    Some(first) => duplicates.push((row, *first)),
+                    None => {
+                        first_rows.insert(key, row);
+                    }
+                }
+            }
+            Ok(duplicates)
+        }
+    }
+
+    /// Constraints on the table as a whole rather than on single columns.
+    #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+    pub struct TableConstraint {
+        /// Rows in the file the set was profiled from.
+        pub profiled_rows: Option<u32>,
+        pub min_rows: Option<u32>,
+        pub max_rows: Option<u32>,
+        /// Minimum rows as a fraction of `profiled_rows`.
+        pub min_rows_ratio: Option<f64>,
+        /// Maximum rows as a fraction of `profiled_rows`.
+        pub max_rows_ratio: Option<f64>,
+        pub column_count: Option<u32>,
+        /// File size bounds in bytes.
+        pub min_file_size: Option<u64>,
+        pub max_file_size: Option<u64>,
+        pub expected_rows_from: Option<RowCountSource>,
+        /// Enabled when the profiled file has no duplicated rows.
+        pub duplicate_rows: Option<DuplicateRows>,
+        /// Severity of table checks by check name, errors by default.
+        #[serde(default)]
+        pub severities: BTreeMap<String, Severity>,
+    }
+
+    impl TableConstraint {
+        pub fn severity_of(&self, check: &str) -> Severity {
+            self.severities.get(check).copied().unwrap_or_default()
+        }
+
+        fn _relative(&self, ratio: Option<f64>) -> Option<u32> {
+            Some((self.profiled_rows? as f64 * ratio?).round() as u32)
+        }
+
+        /// The tightest lower row bound, absolute or relative.
+        pub fn min_rows_bound(&self) -> Option<u32> {
+            match (self.min_rows, self._relative(self.min_rows_ratio)) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            }
+        }
+
+        /// The tightest upper row bound, absolute or relative.
+        pub fn max_rows_bound(&self) -> Option<u32> {
+            match (self.max_rows, self._relative(self.max_rows_ratio)) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            }
+        }
+
+        pub fn new(data: &DataFrame) -> TableConstraint {
+            let duplicate_rows = DuplicateRows::default();
+            TableConstraint {
+                profiled_rows: data.height().to_u32(),
+                column_count: data.width().to_u32(),
+                duplicate_rows: duplicate_rows.find(data).is_ok_and(|d| d.is_empty()).then_some(duplicate_rows),
+                ..Default::default()
+            }
+        }
+    }
+
+    /// Version of the constraints file format written by this release.
+    /// Files without a `format_version` are version 1.
+    pub const FORMAT_VERSION: u32 = 2;
+
+    fn _legacy_format_version() -> u32 {
+        1
+    }
+
+    /// Upgrades a version 1 file. Bounds were always the extremes of the
+    /// column, before `bounds_strategy` recorded it.
+    fn _migrate_v1(set: &mut serde_json::Value) {
+        let constraints = set.get_mut("set").and_then(serde_json::Value::as_array_mut);
+        for constraint in constraints.into_iter().flatten() {
+            let Some(fields) = constraint.as_object_mut() else { continue };
+            let has_bounds = !fields.get("min_value").unwrap_or(&serde_json::Value::Null).is_null();
+            let has_strategy = !fields.get("bounds_strategy").unwrap_or(&serde_json::Value::Null).is_null();
+            if has_bounds && !has_strategy {
+                fields.insert(String::from("bounds_strategy"), serde_json::json!({"strategy": "min_max"}));
+            }
         }
     }
 
+    /// Upgrades a constraints file to `FORMAT_VERSION`, one version at a
+    /// time. Returns the version the file had.
+    pub fn migrate(set: &mut serde_json::Value) -> Result<u32, String> {
+        let version = match set.get("format_version") {
+            Some(version) => version
+                .as_u64()
+                .map(|v| v as u32)
+                .ok_or_else(|| format!("Invalid format_version: {}", version))?,
+            None => _legacy_format_version(),
+        };
+        if version > FORMAT_VERSION {
+            return Err(format!(
+                "Constraints format version {} is newer than the supported version {}",
+                version, FORMAT_VERSION
+            ));
+        }
+        if version < 2 {
+            _migrate_v1(set);
+        }
+        if let Some(fields) = set.as_object_mut() {
+            fields.insert(String::from("format_version"), FORMAT_VERSION.into());
+        }
+        Ok(version)
+    }
+
+    /// Where a constraint set comes from and who maintains it.
+    #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+    pub struct Metadata {
+        pub description: Option<String>,
+        /// Owner or contact of the data.
+        pub owner: Option<String>,
+        /// File the set was profiled from.
+        pub source_path: Option<String>,
+        /// SHA-256 of the source file, in hex.
+        pub source_sha256: Option<String>,
+        /// Version of dvrs that profiled the data.
+        pub tool_version: Option<String>,
+        /// When the set was profiled, in RFC 3339.
+        pub created_at: Option<String>,
+    }
+
+    /// SHA-256 of a file, in hex.
+    pub fn file_sha256(filepath: &str) -> Result<String, String> {
+        let bytes = std::fs::read(filepath).map_err(|err| err.to_string())?;
+        Ok(Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
     #[derive(Serialize, Deserialize, Debug, PartialEq)]
     pub struct ConstraintSet {
+        #[serde(default = "_legacy_format_version")]
+        pub format_version: u32,
         pub name: String,
+        #[serde(default)]
+        pub metadata: Metadata,
+        #[serde(default)]
+        pub name_matching: NameMatching,
         pub set: Vec<Constraint>,
+        #[serde(default)]
+        pub table: TableConstraint,
+    }
+
+    /// The float columns of `data` read again from its csv file as text,
+    /// keeping digits like the trailing zero of `12.50` that parsing drops.
+    fn _float_texts(data: &DataFrame, file_path: &str) -> PolarsResult<DataFrame> {
+        let floats: Vec<String> = data
+            .get_columns()
+            .iter()
+            .filter(|col| matches!(col.dtype(), DataType::Float32 | DataType::Float64))
+            .map(|col| col.name().to_string())
+            .collect();
+        if floats.is_empty() {
+            return Ok(DataFrame::default());
+        }
+        let schema: Schema = floats.iter().map(|name| Field::new(name, DataType::Utf8)).collect();
+        CsvReader::from_path(file_path)?
+            .with_dtypes(Some(&schema))
+            .with_columns(Some(floats))
+            .finish()
     }
 
     impl ConstraintSet {
-        pub fn new(data: &DataFrame) -> ConstraintSet {
+        pub fn new(data: &DataFrame, name: &str) -> ConstraintSet {
+            Self::infer(data, name, &InferenceOptions::default())
+        }
+
+        pub fn infer(data: &DataFrame, name: &str, options: &InferenceOptions) -> ConstraintSet {
             let columns: Vec<&str> = data.get_column_names();
             let mut constraint_set: Vec<Constraint> = vec![];
             for col in columns {
-                let constraint = Constraint::new(&data, &col);
+                let constraint = Constraint::infer(data, col, options);
                 constraint_set.push(constraint)
             }
             ConstraintSet {
-                name: String::from("XXX"),
+                format_version: FORMAT_VERSION,
+                name: String::from(name),
+                metadata: Metadata {
+                    tool_version: Some(String::from(env!("CARGO_PKG_VERSION"))),
+                    created_at: Some(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+                    ..Default::default()
+                },
+                name_matching: NameMatching::default(),
                 set: constraint_set,
+                table: TableConstraint::new(data),
+            }
+        }
+
+        /// Column of the data matched by each constraint, by name, then by
+        /// alias, then ignoring case and whitespace if `name_matching` allows
+        /// it. A column is matched by one constraint at most.
+        pub fn match_columns(&self, data: &DataFrame) -> Vec<Option<String>> {
+            let headers = data.get_column_names();
+            let normalize = |name: &str| -> String {
+                name.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect()
+            };
+            let mut taken: HashSet<&str> = self
+                .set
+                .iter()
+                .map(|c| c.name.as_str())
+                .filter(|name| headers.contains(name))
+                .collect();
+            let mut matches = vec![];
+            for constraint in &self.set {
+                if headers.contains(&constraint.name.as_str()) {
+                    matches.push(Some(constraint.name.clone()));
+                    continue;
+                }
+                let names: Vec<&str> = std::iter::once(constraint.name.as_str())
+                    .chain(constraint.aliases.iter().map(String::as_str))
+                    .collect();
+                let free = headers.iter().copied().filter(|h| !taken.contains(h));
+                let mut found = names.iter().find_map(|name| free.clone().find(|h| h == name));
+                if found.is_none() && self.name_matching == NameMatching::Insensitive {
+                    found = names
+                        .iter()
+                        .find_map(|name| free.clone().find(|h| normalize(h) == normalize(name)));
+                }
+                if let Some(header) = found {
+                    taken.insert(header);
+                }
+                matches.push(found.map(String::from));
+            }
+            matches
+        }
+
+        /// The data with the columns matched through an alias or ignoring
+        /// case renamed to their constraint names.
+        pub fn align_columns(&self, data: &DataFrame) -> PolarsResult<DataFrame> {
+            let mut aligned = data.clone();
+            for (constraint, matched) in self.set.iter().zip(self.match_columns(data)) {
+                if let Some(header) = matched.filter(|header| *header != constraint.name) {
+                    aligned.rename(&header, &constraint.name)?;
+                }
             }
+            Ok(aligned)
         }
 
-        pub fn modify(&mut self, name: &str, ctype: &str, value: &str) -> () {
+        /// Profiles data read from `file_path`. The set is named after the
+        /// file and records its path and hash.
+        pub fn for_file(data: &DataFrame, options: &InferenceOptions, file_path: &str) -> ConstraintSet {
+            let name = Path::new(file_path).file_stem().map(|stem| stem.to_string_lossy());
+            let mut set = Self::infer(data, name.as_deref().unwrap_or(file_path), options);
+            set.metadata.source_path = Some(String::from(file_path));
+            set.metadata.source_sha256 = file_sha256(file_path).ok();
+            if let Ok(texts) = _float_texts(data, file_path) {
+                for constraint in set.set.iter_mut() {
+                    if let Ok(col) = texts.column(&constraint.name) {
+                        let digits = Constraint::_max_decimal_digits(col);
+                        constraint.precision = digits.map(|(precision, _)| precision);
+                        constraint.scale = digits.map(|(_, scale)| scale);
+                    }
+                }
+            }
+            set
+        }
+
+        /// Updates a table-level constraint. Numbers ending in `%` set the
+        /// row bounds relative to the profiled row count,
+        /// `severity.<check>` sets the severity of a table check and
+        /// `name_matching` how columns are matched to constraints.
+        pub fn modify_table(&mut self, ctype: &str, value: &str) {
+            if ctype == "name_matching" {
+                match NameMatching::from_str(value) {
+                    Ok(matching) => self.name_matching = matching,
+                    Err(e) => println!("{:?}", e),
+                }
+                return;
+            }
+            let table = &mut self.table;
+            let ratio = value
+                .strip_suffix('%')
+                .and_then(|v| f64::from_str(v).ok())
+                .map(|v| v / 100.0);
+            match ctype {
+                "min_rows" if ratio.is_some() => table.min_rows_ratio = ratio,
+                "max_rows" if ratio.is_some() => table.max_rows_ratio = ratio,
+                "min_rows" => table.min_rows = u32::from_str(value).ok(),
+                "max_rows" => table.max_rows = u32::from_str(value).ok(),
+                "column_count" => table.column_count = u32::from_str(value).ok(),
+                "min_file_size" => table.min_file_size = u64::from_str(value).ok(),
+                "max_file_size" => table.max_file_size = u64::from_str(value).ok(),
+                "expected_rows_from" => {
+                    table.expected_rows_from = match value {
+                        "" => None,
+                        "trailer" => Some(RowCountSource::Trailer),
+                        path => Some(RowCountSource::ControlFile(String::from(path))),
+                    }
+                }
+                "duplicate_rows" => {
+                    table.duplicate_rows = bool::from_str(value)
+                        .unwrap_or_default()
+                        .then(DuplicateRows::default)
+                }
+                severity if severity.starts_with("severity.") => match Severity::from_str(value) {
+                    Ok(level) => {
+                        table.severities.insert(severity["severity.".len()..].to_string(), level);
+                    }
+                    Err(e) => println!("{:?}", e),
+                },
+                _ => println!("{:?}", "Please provide a valid table constraint name."),
+            }
+        }
+
+        /// Updates a constraint of a column. `severity` sets the severity of
+        /// all its checks and `severity.<check>` the severity of one check.
+        pub fn modify(&mut self, name: &str, ctype: &str, value: &str) {
             if let Some(constraint) = self.set.iter_mut().find(|c| c.name == name) {
                 match ctype {
-                    "data_type" => match value {
-                        "str" => constraint.data_type = DataType::Utf8.to_string(),
-                        "int" => constraint.data_type = DataType::Int32.to_string(),
-                        "float" => constraint.data_type = DataType::Float64.to_string(),
-                        "date" => constraint.data_type = DataType::Date.to_string(),
-                        _ => constraint.data_type = DataType::Null.to_string(),
+                    "data_type" => match ColumnType::from_str(value) {
+                        Ok(data_type) => constraint.data_type = data_type,
+                        Err(e) => println!("{:?}", e),
                     },
                     "nullable" => constraint.nullable = bool::from_str(value).unwrap_or_default(),
                     "unique" => constraint.unique = bool::from_str(value).unwrap_or_default(),
@@ -183,6 +1408,50 @@ pub mod constraints {
                     "min_value" => constraint.min_value = f64::from_str(value).ok(),
                     "max_value" => constraint.max_value = f64::from_str(value).ok(),
                     "value_range" => constraint.value_range = String::from(value).into(),
+                    "description" => constraint.description = Some(String::from(value)).filter(|v| !v.is_empty()),
+                    "aliases" => {
+                        constraint.aliases = value
+                            .split(',')
+                            .map(|alias| String::from(alias.trim()))
+                            .filter(|alias| !alias.is_empty())
+                            .collect()
+                    }
+                    "semantic_type" => constraint.semantic_type = SemanticType::from_str(value).ok(),
+                    "precision" => constraint.precision = u32::from_str(value).ok(),
+                    "scale" => constraint.scale = u32::from_str(value).ok(),
+                    "integer_only" => constraint.integer_only = bool::from_str(value).unwrap_or_default(),
+                    "positive" => constraint.positive = bool::from_str(value).unwrap_or_default(),
+                    "non_negative" => constraint.non_negative = bool::from_str(value).unwrap_or_default(),
+                    "multiple_of" => constraint.multiple_of = f64::from_str(value).ok(),
+                    "z_score" => constraint.z_score = f64::from_str(value).ok(),
+                    "iqr_factor" => constraint.iqr_factor = f64::from_str(value).ok(),
+                    "monotonic" => constraint.monotonic = Monotonic::from_str(value).ok(),
+                    "no_gaps" => constraint.no_gaps = bool::from_str(value).unwrap_or_default(),
+                    "trimmed" => constraint.trimmed = bool::from_str(value).unwrap_or_default(),
+                    "case" => constraint.case = Case::from_str(value).ok(),
+                    "ascii_only" => constraint.ascii_only = bool::from_str(value).unwrap_or_default(),
+                    "allowed_chars" => constraint.allowed_chars = Some(String::from(value)).filter(|v| !v.is_empty()),
+                    "no_control_chars" => constraint.no_control_chars = bool::from_str(value).unwrap_or_default(),
+                    "repairs.fill_null" => constraint.repairs.fill_null = Some(String::from(value)),
+                    "repairs.trim" => constraint.repairs.trim = bool::from_str(value).unwrap_or_default(),
+                    "repairs.map_case" => constraint.repairs.map_case = bool::from_str(value).unwrap_or_default(),
+                    "repairs.truncate" => constraint.repairs.truncate = bool::from_str(value).unwrap_or_default(),
+                    "repairs.clamp" => constraint.repairs.clamp = bool::from_str(value).unwrap_or_default(),
+                    "severity" => match Severity::from_str(value) {
+                        Ok(level) => constraint.severity = level,
+                        Err(e) => println!("{:?}", e),
+                    },
+                    severity if severity.starts_with("severity.") => match Severity::from_str(value) {
+                        Ok(level) => {
+                            constraint.severities.insert(severity["severity.".len()..].to_string(), level);
+                        }
+                        Err(e) => println!("{:?}", e),
+                    },
+                    aggregate if AGGREGATES.contains(&aggregate) => {
+                        constraint
+                            .aggregates
+                            .set_range(aggregate, AggregateRange::from_str(value).ok());
+                    }
                     _ => println!("{:?}", "Please provide a valid constraint name."),
                 }
                 println!("Constraint updated:");
@@ -192,38 +1461,113 @@ pub mod constraints {
             }
         }
 
+        /// Saves the set in the format matching the file extension
+        /// (`.yaml`/`.yml`, `.toml`), falling back to JSON.
+        pub fn save(&self, filepath: &str) -> Result<(), String> {
+            match FileFormat::from_path(filepath) {
+                FileFormat::Json => self.save_json(filepath),
+                FileFormat::Yaml => self.save_yaml(filepath),
+                FileFormat::Toml => self.save_toml(filepath),
+            }
+        }
+
         pub fn save_json(&self, filepath: &str) -> Result<(), String> {
 
             let json = serde_json::to_string_pretty(self).map_err(|err| err.to_string())?;
 
             let path = Path::new(filepath);
-            let mut file = File::create(&path).map_err(|err| err.to_string())?;
+            let mut file = File::create(path).map_err(|err| err.to_string())?;
             file.write_all(json.as_bytes()).map_err(|err| err.to_string())?;
 
             Ok(())
         }
 
+        pub fn save_yaml(&self, filepath: &str) -> Result<(), String> {
+            let yaml = serde_yaml::to_string(self).map_err(|err| err.to_string())?;
+            Self::_write_with_comments(filepath, &yaml)
+        }
+
+        pub fn save_toml(&self, filepath: &str) -> Result<(), String> {
+            let toml = toml::to_string_pretty(self).map_err(|err| err.to_string())?;
+            Self::_write_with_comments(filepath, &toml)
+        }
+
+        /// YAML and TOML files are often edited by hand. Comments can't survive
+        /// a serde round trip: only the comment block at the top of an existing
+        /// file is kept when it gets overwritten, comments elsewhere are lost.
+        fn _write_with_comments(filepath: &str, contents: &str) -> Result<(), String> {
+            let path = Path::new(filepath);
+            let header: String = std::fs::read_to_string(path)
+                .unwrap_or_default()
+                .lines()
+                .take_while(|line| line.trim_start().starts_with('#') || line.trim().is_empty())
+                .map(|line| format!("{}\n", line))
+                .collect();
+
+            let mut file = File::create(path).map_err(|err| err.to_string())?;
+            file.write_all(header.as_bytes()).map_err(|err| err.to_string())?;
+            file.write_all(contents.as_bytes()).map_err(|err| err.to_string())?;
+
+            Ok(())
+        }
+
+        /// Reads a constraint file in the format matching its extension.
+        /// Files written by older releases are migrated, with a warning.
         pub fn read_constraints(filepath: &str) -> Result<ConstraintSet, Box<dyn std::error::Error>> {
             let mut file = File::open(filepath)?;
             let mut contents = String::new();
             file.read_to_string(&mut contents)?;
-            let set: ConstraintSet = serde_json::from_str(&contents)?;
-            Ok(set)
+            let mut value: serde_json::Value = match FileFormat::from_path(filepath) {
+                FileFormat::Json => serde_json::from_str(&contents)?,
+                FileFormat::Yaml => serde_yaml::from_str(&contents)?,
+                FileFormat::Toml => toml::from_str(&contents)?,
+            };
+            let version = migrate(&mut value)?;
+            if version < FORMAT_VERSION {
+                eprintln!(
+                    "Warning: {} uses constraints format version {}, upgraded to version {}. Save it again to keep the upgrade.",
+                    filepath, version, FORMAT_VERSION
+                );
+            }
+            Ok(serde_json::from_value(value)?)
+        }
+    }
+
+    impl fmt::Display for Metadata {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            for (label, value) in [
+                ("Description", &self.description),
+                ("Owner", &self.owner),
+                ("Source", &self.source_path),
+                ("Source SHA-256", &self.source_sha256),
+                ("Created", &self.created_at),
+                ("dvrs version", &self.tool_version),
+            ] {
+                if let Some(value) = value {
+                    writeln!(f, "{}: {}", label, value)?;
+                }
+            }
+            Ok(())
         }
     }
 
     impl fmt::Display for ConstraintSet {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            writeln!(f, "Constraints: {}", self.name).unwrap_or_default();
+            write!(f, "{}", self.metadata).unwrap_or_default();
+            if let Some(rows) = self.table.profiled_rows {
+                writeln!(f, "Rows profiled: {}", rows).unwrap_or_default();
+            }
             let mut max_length: usize = 0;
             for constraint in &self.set {
                 if constraint.name.len().to_usize().unwrap_or_default() > max_length {
                     max_length = constraint.name.len().to_usize().unwrap_or_default()
                 }
             }
-            write!(f, "+{:<}+\n", "-".repeat(max_length + 176)).unwrap_or_default();
-            write!(
+            writeln!(f, "+{:<}+", "-".repeat(max_length + 176)).unwrap_or_default();
+            writeln!(
                 f,
-                "|{:<width1$}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<56}|\n",
+                "|{:<width1$}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<56}|",
                 "Name",
                 "Data Type",
                 "Nullable",
@@ -236,7 +1580,7 @@ pub mod constraints {
                 width1 = max_length,
             )
             .unwrap_or_default();
-            write!(f, "+{:<}+\n", "-".repeat(max_length + 176)).unwrap_or_default();
+            writeln!(f, "+{:<}+", "-".repeat(max_length + 176)).unwrap_or_default();
             for constraint in &self.set {
                 let range_string = constraint
                     .value_range
@@ -248,9 +1592,9 @@ pub mod constraints {
                 } else {
                     &range_string
                 };
-                write!(
+                writeln!(
                     f,
-                    "|{:<width1$}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<56}|\n",
+                    "|{:<width1$}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<56}|",
                     constraint.name,
                     constraint.data_type.to_string(),
                     constraint.nullable,
@@ -262,13 +1606,34 @@ pub mod constraints {
                     trimmed_range,
                     width1 = max_length,
                 ).unwrap_or_default();
-                write!(f, "+{:<}+\n", "-".repeat(max_length + 176)).unwrap_or_default();
+                writeln!(f, "+{:<}+", "-".repeat(max_length + 176)).unwrap_or_default();
+            }
+            for constraint in &self.set {
+                if let Some(description) = &constraint.description {
+                    writeln!(f, "{}: {}", constraint.name, description).unwrap_or_default();
+                }
+                if let Some(suggested_type) = &constraint.suggested_type {
+                    writeln!(
+                        f,
+                        "{}: values look like {}",
+                        constraint.name, suggested_type
+                    ).unwrap_or_default();
+                }
+                if let Some(semantic_type) = &constraint.semantic_type {
+                    writeln!(
+                        f,
+                        "{}: values are {}",
+                        constraint.name, semantic_type
+                    ).unwrap_or_default();
+                }
             }
             Ok(())
         }
     }
 }
 
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
 
     use super::constraints::*;
@@ -284,7 +1649,7 @@ mod tests {
         // Test constraint creation.
         let constraint = Constraint::new(&df, "age");
         assert_eq!(constraint.name, "age");
-        assert_eq!(constraint.data_type, "f64");
+        assert_eq!(constraint.data_type, ColumnType::Float64);
         assert_eq!(constraint.nullable, true);
         assert_eq!(constraint.unique, false);
         assert_eq!(constraint.min_length, None);
@@ -292,10 +1657,11 @@ mod tests {
         assert_eq!(constraint.min_value, Some(0.08));
         assert_eq!(constraint.max_value, Some(82.00));
         assert_eq!(constraint.value_range, None);
+        assert_eq!(constraint.bounds_strategy, Some(BoundsStrategy::MinMax));
 
         let constraint = Constraint::new(&df, "Residence_type");
         assert_eq!(constraint.name, "Residence_type");
-        assert_eq!(constraint.data_type, "str");
+        assert_eq!(constraint.data_type, ColumnType::Utf8);
         assert_eq!(constraint.nullable, false);
         assert_eq!(constraint.unique, false);
         assert_eq!(constraint.min_length, Some(5));
@@ -313,10 +1679,392 @@ mod tests {
             .unwrap();
 
         // Test save and load constraint set
-        let set = ConstraintSet::new(&df);
-        set.save_json("test_data/saved_constraints.json");
+        let set = ConstraintSet::new(&df, "test");
+        set.save_json("test_data/saved_constraints.json").unwrap();
         let new_set = ConstraintSet::read_constraints("test_data/saved_constraints.json");
         assert_eq!(set, new_set.unwrap());
     }
 
+    #[test]
+    fn migrate_legacy_file() {
+        let legacy = r#"{"name": "XXX", "set": [
+            {"name": "age", "data_type": "i64", "nullable": false, "unique": false, "min_length": null,
+             "max_length": null, "min_value": 1.0, "max_value": 82.0, "value_range": null}
+        ]}"#;
+        let path = std::env::temp_dir().join("dvrs_legacy_constraints.json");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, legacy).unwrap();
+
+        let set = ConstraintSet::read_constraints(path).unwrap();
+        assert_eq!(set.format_version, FORMAT_VERSION);
+        assert_eq!(set.set[0].data_type, ColumnType::Int64);
+        assert_eq!(set.set[0].bounds_strategy, Some(BoundsStrategy::MinMax));
+        assert_eq!(set.set[0].severity, Severity::Error);
+
+        let mut newer = serde_json::json!({"format_version": FORMAT_VERSION + 1, "name": "XXX", "set": []});
+        assert!(migrate(&mut newer).is_err());
+    }
+
+    #[test]
+    fn set_metadata() {
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        let mut set = ConstraintSet::for_file(&df, &InferenceOptions::default(), "test_data/brain_stroke.csv");
+        assert_eq!(set.name, "brain_stroke");
+        assert_eq!(set.metadata.source_path.as_deref(), Some("test_data/brain_stroke.csv"));
+        let hash = set.metadata.source_sha256.clone().unwrap();
+        assert_eq!(hash.len(), 64);
+        assert_eq!(file_sha256("test_data/brain_stroke.csv").unwrap(), hash);
+        assert_eq!(set.metadata.tool_version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+        assert!(chrono::DateTime::parse_from_rfc3339(set.metadata.created_at.as_deref().unwrap()).is_ok());
+
+        set.metadata.owner = Some(String::from("data-team@example.com"));
+        set.modify("age", "description", "Age in years");
+        assert_eq!(set.set[1].description.as_deref(), Some("Age in years"));
+        let report = set.to_string();
+        assert!(report.starts_with("Constraints: brain_stroke\n"));
+        assert!(report.contains("Owner: data-team@example.com\n"));
+        assert!(report.contains("age: Age in years\n"));
+    }
+
+    #[test]
+    fn save_and_load_yaml_toml() {
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        let set = ConstraintSet::new(&df, "test");
+        for ext in ["yaml", "toml"] {
+            let path = std::env::temp_dir().join(format!("dvrs_constraints.{}", ext));
+            let path = path.to_str().unwrap();
+            std::fs::write(path, "# reviewed by data team\n").unwrap();
+            set.save(path).unwrap();
+
+            let contents = std::fs::read_to_string(path).unwrap();
+            assert!(contents.starts_with("# reviewed by data team\n"));
+            let new_set = ConstraintSet::read_constraints(path);
+            assert_eq!(set, new_set.unwrap());
+        }
+    }
+
+    #[test]
+    fn quantile_bounds() {
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        let options = InferenceOptions {
+            bounds: BoundsStrategy::Quantile {
+                lower: 0.01,
+                upper: 0.99,
+                margin: 0.0,
+            },
+            ..Default::default()
+        };
+        let constraint = Constraint::infer(&df, "avg_glucose_level", &options);
+        assert!(constraint.min_value.unwrap() > -25.0);
+        assert!(constraint.max_value.unwrap() < 700.0);
+        assert_eq!(constraint.bounds_strategy, Some(options.bounds.clone()));
+
+        let options = InferenceOptions {
+            bounds: BoundsStrategy::Quantile {
+                lower: 0.0,
+                upper: 1.0,
+                margin: 0.5,
+            },
+            ..Default::default()
+        };
+        let constraint = Constraint::infer(&df, "hypertension", &options);
+        assert_eq!(constraint.min_value, Some(-1.0));
+        assert_eq!(constraint.max_value, Some(3.0));
+
+        let constraint = Constraint::infer(&df, "gender", &options);
+        assert_eq!(constraint.min_value, None);
+        assert_eq!(constraint.bounds_strategy, None);
+
+        assert_eq!(BoundsStrategy::quantile(0.0, 1.0, 0.5), Ok(options.bounds));
+        assert!(BoundsStrategy::quantile(0.99, 0.01, 0.0).is_err());
+        assert!(BoundsStrategy::quantile(-0.1, 0.9, 0.0).is_err());
+        assert!(BoundsStrategy::quantile(0.1, f64::NAN, 0.0).is_err());
+    }
+
+    #[test]
+    fn suggest_types() {
+        let df = df!(
+            "amount" => &["1.5", "2", "n/a", "3.25", "4", "5", "6", "7", "8", "9", "10", "11",
+                          "12", "13", "14", "15", "16", "17", "18", "19", "20"],
+            "flag" => &["Y", "N", "y", "N", "Y", "N", "Y", "N", "Y", "N", "Y", "N",
+                        "Y", "N", "Y", "N", "Y", "N", "Y", "N", "Y"],
+            "day" => &["2023-01-01", "2023-01-02", "2023/01/03", "04/01/2023", "2023-01-05",
+                       "2023-01-06", "2023-01-07", "2023-01-08", "2023-01-09", "2023-01-10",
+                       "2023-01-11", "2023-01-12", "2023-01-13", "2023-01-14", "2023-01-15",
+                       "2023-01-16", "2023-01-17", "2023-01-18", "2023-01-19", "2023-01-20",
+                       "2023-01-21"]
+        )
+        .unwrap();
+
+        assert_eq!(Constraint::new(&df, "amount").suggested_type, Some(ColumnType::Float64));
+        assert_eq!(Constraint::new(&df, "flag").suggested_type, Some(ColumnType::Bool));
+        assert_eq!(Constraint::new(&df, "day").suggested_type, Some(ColumnType::Date));
+
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        assert_eq!(Constraint::new(&df, "ever_married").suggested_type, Some(ColumnType::Bool));
+        assert_eq!(Constraint::new(&df, "gender").suggested_type, None);
+        assert_eq!(Constraint::new(&df, "age").suggested_type, None);
+    }
+
+    #[test]
+    fn modify_severity() {
+        let df = df!("status" => &["a", "b"]).unwrap();
+        let mut set = ConstraintSet::new(&df, "test");
+        assert_eq!(set.set[0].severity_of("value_range"), Severity::Error);
+
+        set.modify("status", "severity", "warning");
+        set.modify("status", "severity.nullable", "error");
+        set.modify_table("severity.duplicate_rows", "info");
+        assert_eq!(set.set[0].severity_of("value_range"), Severity::Warning);
+        assert_eq!(set.set[0].severity_of("nullable"), Severity::Error);
+        assert_eq!(set.table.severity_of("duplicate_rows"), Severity::Info);
+        assert_eq!(set.table.severity_of("min_rows"), Severity::Error);
+    }
+
+    #[test]
+    fn modify_data_type() {
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        let mut set = ConstraintSet::new(&df, "test");
+        set.modify("age", "data_type", "numeric");
+        assert_eq!(set.set[1].data_type, ColumnType::Numeric);
+        set.modify("age", "data_type", "u16");
+        assert_eq!(set.set[1].data_type, ColumnType::UInt16);
+    }
+
+    #[test]
+    fn detect_semantic_type() {
+        let df = df!(
+            "email" => &["a@example.com", "b@example.org", "c@example.net"],
+            "country" => &["AR", "US", "DE"],
+            "name" => &["Ana", "Bob", "Carl"]
+        )
+        .unwrap();
+
+        assert_eq!(Constraint::new(&df, "email").semantic_type, Some(SemanticType::Email));
+        assert_eq!(Constraint::new(&df, "country").semantic_type, Some(SemanticType::CountryCode));
+        assert_eq!(Constraint::new(&df, "name").semantic_type, None);
+    }
+
+    #[test]
+    fn precision_and_scale() {
+        assert_eq!(decimal_digits("-0012.50"), Some((4, 2)));
+        assert_eq!(decimal_digits("1000"), Some((4, 0)));
+        assert_eq!(decimal_digits("1e5"), None);
+
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let constraint = Constraint::new(&df, "avg_glucose_level");
+        assert_eq!(constraint.scale, Some(2));
+        assert_eq!(constraint.precision, Some(5));
+        assert_eq!(Constraint::new(&df, "gender").precision, None);
+
+        let df = df!("amount" => &["0.1", "0.2", "1234.56"], "total" => &[0.1 + 0.2, 1.5, 2.0]).unwrap();
+        let constraint = Constraint::new(&df, "amount");
+        assert_eq!((constraint.precision, constraint.scale), (Some(6), Some(2)));
+        let constraint = Constraint::new(&df, "total");
+        assert_eq!(constraint.scale, Some(17));
+
+        let path = std::env::temp_dir().join("dvrs_trailing_zeros.csv");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "price,count\n12.50,1\n3.10,2\n").unwrap();
+        let df: DataFrame = CsvReader::from_path(path).unwrap().finish().unwrap();
+        assert_eq!(Constraint::new(&df, "price").scale, Some(1));
+        let set = ConstraintSet::for_file(&df, &InferenceOptions::default(), path);
+        assert_eq!((set.set[0].precision, set.set[0].scale), (Some(4), Some(2)));
+        assert_eq!(set.set[1].precision, None);
+    }
+
+    #[test]
+    fn integer_and_sign() {
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let constraint = Constraint::new(&df, "hypertension");
+        assert!(constraint.integer_only);
+        assert!(!constraint.positive);
+        assert!(constraint.non_negative);
+        assert_eq!(constraint.multiple_of, None);
+
+        let constraint = Constraint::new(&df, "avg_glucose_level");
+        assert!(!constraint.integer_only);
+        assert!(!constraint.non_negative);
+
+        let df = df!("count" => &[10, 25, 5]).unwrap();
+        let constraint = Constraint::new(&df, "count");
+        assert!(constraint.positive);
+        assert_eq!(constraint.multiple_of, Some(5.0));
+    }
+
+    #[test]
+    fn table_constraints() {
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let mut set = ConstraintSet::new(&df, "test");
+        assert_eq!(set.table.profiled_rows, Some(4981));
+        assert_eq!(set.table.column_count, Some(10));
+
+        set.modify_table("min_rows", "90%");
+        set.modify_table("min_rows", "100");
+        set.modify_table("max_rows", "5000");
+        set.modify_table("max_rows", "200%");
+        assert_eq!(set.table.min_rows_bound(), Some(4483));
+        assert_eq!(set.table.max_rows_bound(), Some(5000));
+    }
+
+    #[test]
+    fn find_duplicate_rows() {
+        let df = df!(
+            "id" => &[1, 2, 3, 4],
+            "name" => &["Ana", "Bob", " ana ", "Bob"]
+        )
+        .unwrap();
+
+        let check = DuplicateRows::default();
+        assert_eq!(check.find(&df), Ok(vec![]));
+
+        let check = DuplicateRows {
+            columns: vec![String::from("name")],
+            ..Default::default()
+        };
+        assert_eq!(check.find(&df), Ok(vec![(3, 1)]));
+
+        let check = DuplicateRows {
+            columns: vec![String::from("name")],
+            ignore_whitespace: true,
+            ignore_case: true,
+        };
+        assert_eq!(check.find(&df), Ok(vec![(2, 0), (3, 1)]));
+
+        let check = DuplicateRows {
+            columns: vec![String::from("name"), String::from("email")],
+            ..Default::default()
+        };
+        assert!(check.find(&df).is_err());
+    }
+
+    #[test]
+    fn column_stats() {
+        let df = df!("value" => &[1.0, 2.0, 3.0, 4.0, 5.0], "name" => &["a", "b", "c", "d", "e"]).unwrap();
+        let options = InferenceOptions {
+            z_score: Some(3.0),
+            iqr_factor: Some(1.5),
+            ..Default::default()
+        };
+
+        let constraint = Constraint::infer(&df, "value", &options);
+        let stats = constraint.stats.unwrap();
+        assert_eq!(stats.mean, 3.0);
+        assert_eq!(stats.std_dev, 2.5_f64.sqrt());
+        assert_eq!((stats.q1, stats.median, stats.q3), (2.0, 3.0, 4.0));
+        assert_eq!(constraint.z_score, Some(3.0));
+
+        let constraint = Constraint::infer(&df, "name", &options);
+        assert_eq!(constraint.stats, None);
+        assert_eq!(constraint.iqr_factor, None);
+    }
+
+    #[test]
+    fn aggregate_ranges() {
+        let df = df!("value" => &[Some(-2.0), Some(4.0), None, Some(4.0)]).unwrap();
+        let col = df.column("value").unwrap();
+        assert_eq!(aggregate(col, "mean"), Some(2.0));
+        assert_eq!(aggregate(col, "median"), Some(4.0));
+        assert_eq!(aggregate(col, "sum"), Some(6.0));
+        assert_eq!(aggregate(col, "distinct_count"), Some(2.0));
+        assert_eq!(aggregate(col, "null_count"), Some(1.0));
+
+        assert_eq!(ConstraintSet::new(&df, "test").set[0].aggregates, Aggregates::default());
+        let options = InferenceOptions {
+            aggregate_tolerance: Some(0.1),
+            ..Default::default()
+        };
+        let mut set = ConstraintSet::infer(&df, "test", &options);
+        let aggregates = &set.set[0].aggregates;
+        assert_eq!(aggregates.mean, Some(AggregateRange { min: 1.8, max: 2.2 }));
+        assert_eq!(aggregates.null_count, None);
+
+        set.modify("value", "sum", "0,10");
+        assert_eq!(set.set[0].aggregates.sum, Some(AggregateRange { min: 0.0, max: 10.0 }));
+        set.modify("value", "sum", "");
+        assert_eq!(set.set[0].aggregates.sum, None);
+    }
+
+    #[test]
+    fn monotonic_and_gaps() {
+        let df = df!(
+            "event_id" => &[1, 2, 3, 5],
+            "timestamp" => &["2023-01-01 10:00:00", "2023-01-01 10:00:00", "2023-01-02 09:00:00", "2023-01-03 08:00:00"],
+            "score" => &[3.5, 1.0, 2.0, 2.0]
+        )
+        .unwrap();
+
+        let constraint = Constraint::new(&df, "event_id");
+        assert_eq!(constraint.monotonic, Some(Monotonic::StrictlyIncreasing));
+        assert_eq!(constraint.severity_of("monotonic"), Severity::Warning);
+        assert!(!constraint.no_gaps);
+        assert_eq!(sequence_gaps(df.column("event_id").unwrap()), Some(vec![(4, 4)]));
+        assert_eq!(Constraint::new(&df, "timestamp").monotonic, Some(Monotonic::Increasing));
+        assert_eq!(Constraint::new(&df, "score").monotonic, None);
+        assert_eq!(Monotonic::Decreasing.breaks(df.column("score").unwrap()), vec![2]);
+
+        let df = df!("invoice" => &[12, 10, 11]).unwrap();
+        assert!(Constraint::new(&df, "invoice").no_gaps);
+
+        let df = df!("day" => &["31/01/2024", "01/02/2024", "15/02/2024"]).unwrap();
+        assert_eq!(Constraint::new(&df, "day").monotonic, Some(Monotonic::StrictlyIncreasing));
+        assert_eq!(Monotonic::Increasing.breaks(df.column("day").unwrap()), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn string_hygiene() {
+        let df = df!(
+            "code" => &["ab", "cd", "ef"],
+            "name" => &["Ana María", "Bob", "Carl Jr."],
+            "note" => &[" Padded", "tab\there", "plain"]
+        )
+        .unwrap();
+
+        let constraint = Constraint::new(&df, "code");
+        assert_eq!(constraint.case, Some(Case::Lower));
+        assert!(constraint.trimmed && constraint.ascii_only && constraint.no_control_chars);
+        assert_eq!(constraint.severity_of("ascii_only"), Severity::Warning);
+        assert_eq!(constraint.severity_of("nullable"), Severity::Error);
+
+        let constraint = Constraint::new(&df, "name");
+        assert_eq!(constraint.case, Some(Case::Title));
+        assert!(!constraint.ascii_only);
+
+        let constraint = Constraint::new(&df, "note");
+        assert_eq!(constraint.case, None);
+        assert!(!constraint.trimmed && !constraint.no_control_chars);
+
+        let letters = allowed_chars_pattern(r"\p{L} ").unwrap();
+        assert!(letters.is_match("Ana María"));
+        assert!(!letters.is_match("O'Neil"));
+        assert!(allowed_chars_pattern(r"\p{Nope}").is_none());
+    }
 }
@@ -0,0 +1,224 @@
+pub mod diff {
+    use crate::constraints::constraints::*;
+    use serde::{Deserialize, Serialize};
+    use serde_json::{Map, Value};
+    use std::fmt;
+
+    /// A field whose value differs between two versions.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct FieldChange {
+        pub field: String,
+        pub old: Value,
+        pub new: Value,
+    }
+
+    /// Differences of the constraint of one column.
+    #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+    pub struct ColumnDiff {
+        pub name: String,
+        pub changes: Vec<FieldChange>,
+        /// Members of `value_range` only in the new version.
+        pub added_values: Vec<String>,
+        /// Members of `value_range` only in the old version.
+        pub removed_values: Vec<String>,
+    }
+
+    /// Differences between two versions of a constraint set.
+    #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+    pub struct ConstraintSetDiff {
+        pub added_columns: Vec<String>,
+        pub removed_columns: Vec<String>,
+        pub changed_columns: Vec<ColumnDiff>,
+        pub table: Vec<FieldChange>,
+    }
+
+    impl ColumnDiff {
+        pub fn is_empty(&self) -> bool {
+            self.changes.is_empty() && self.added_values.is_empty() && self.removed_values.is_empty()
+        }
+    }
+
+    impl ConstraintSetDiff {
+        pub fn is_empty(&self) -> bool {
+            self.added_columns.is_empty()
+                && self.removed_columns.is_empty()
+                && self.changed_columns.is_empty()
+                && self.table.is_empty()
+        }
+    }
+
+    fn _fields<T: Serialize>(value: &T) -> Map<String, Value> {
+        match serde_json::to_value(value) {
+            Ok(Value::Object(fields)) => fields,
+            _ => Map::new(),
+        }
+    }
+
+    /// Fields that differ, in the order of the new version. `skip` fields
+    /// are compared separately.
+    fn _field_changes<T: Serialize>(old: &T, new: &T, skip: &[&str]) -> Vec<FieldChange> {
+        let mut changes = vec![];
+        _object_changes("", &_fields(old), &_fields(new), skip, &mut changes);
+        changes
+    }
+
+    /// Compares two objects field by field, going into nested objects so a
+    /// change is reported as e.g. `aggregates.mean.min`. A missing or null
+    /// object compares as an empty one.
+    fn _object_changes(prefix: &str, old: &Map<String, Value>, new: &Map<String, Value>, skip: &[&str], changes: &mut Vec<FieldChange>) {
+        let mut names: Vec<&String> = new.keys().collect();
+        names.extend(old.keys().filter(|k| !new.contains_key(*k)));
+        let empty = Map::new();
+        for name in names.into_iter().filter(|name| !skip.contains(&name.as_str())) {
+            let field = format!("{}{}", prefix, name);
+            let old = old.get(name).unwrap_or(&Value::Null);
+            let new = new.get(name).unwrap_or(&Value::Null);
+            match (old, new) {
+                (Value::Object(old), Value::Object(new)) => _object_changes(&format!("{}.", field), old, new, &[], changes),
+                (Value::Object(old), Value::Null) => _object_changes(&format!("{}.", field), old, &empty, &[], changes),
+                (Value::Null, Value::Object(new)) => _object_changes(&format!("{}.", field), &empty, new, &[], changes),
+                _ if old != new => changes.push(FieldChange {
+                    field,
+                    old: old.clone(),
+                    new: new.clone(),
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    fn _members(constraint: &Constraint) -> Vec<&str> {
+        constraint
+            .value_range
+            .as_deref()
+            .map(|range| range.split(", ").collect())
+            .unwrap_or_default()
+    }
+
+    impl ConstraintSet {
+        /// Compares this set with a newer version, column by column.
+        pub fn diff(&self, new: &ConstraintSet) -> ConstraintSetDiff {
+            let find = |set: &ConstraintSet, name: &str| set.set.iter().position(|c| c.name == name);
+            let mut diff = ConstraintSetDiff {
+                added_columns: new
+                    .set
+                    .iter()
+                    .filter(|c| find(self, &c.name).is_none())
+                    .map(|c| c.name.clone())
+                    .collect(),
+                removed_columns: self
+                    .set
+                    .iter()
+                    .filter(|c| find(new, &c.name).is_none())
+                    .map(|c| c.name.clone())
+                    .collect(),
+                table: _field_changes(&self.table, &new.table, &[]),
+                ..Default::default()
+            };
+            for new_constraint in &new.set {
+                let Some(index) = find(self, &new_constraint.name) else { continue };
+                let old_constraint = &self.set[index];
+                let old_members = _members(old_constraint);
+                let new_members = _members(new_constraint);
+                let column = ColumnDiff {
+                    name: new_constraint.name.clone(),
+                    changes: _field_changes(old_constraint, new_constraint, &["name", "value_range"]),
+                    added_values: new_members
+                        .iter()
+                        .filter(|m| !old_members.contains(m))
+                        .map(|m| m.to_string())
+                        .collect(),
+                    removed_values: old_members
+                        .iter()
+                        .filter(|m| !new_members.contains(m))
+                        .map(|m| m.to_string())
+                        .collect(),
+                };
+                if !column.is_empty() {
+                    diff.changed_columns.push(column);
+                }
+            }
+            diff
+        }
+    }
+
+    impl fmt::Display for FieldChange {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}: {} -> {}", self.field, self.old, self.new)
+        }
+    }
+
+    impl fmt::Display for ConstraintSetDiff {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            if self.is_empty() {
+                return writeln!(f, "No differences");
+            }
+            for name in &self.added_columns {
+                writeln!(f, "+ {}: column added", name)?;
+            }
+            for name in &self.removed_columns {
+                writeln!(f, "- {}: column removed", name)?;
+            }
+            for column in &self.changed_columns {
+                for change in &column.changes {
+                    writeln!(f, "~ {}: {}", column.name, change)?;
+                }
+                if !column.added_values.is_empty() {
+                    writeln!(f, "~ {}: value_range added {}", column.name, column.added_values.join(", "))?;
+                }
+                if !column.removed_values.is_empty() {
+                    writeln!(f, "~ {}: value_range removed {}", column.name, column.removed_values.join(", "))?;
+                }
+            }
+            for change in &self.table {
+                writeln!(f, "~ table: {}", change)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constraints::constraints::*;
+    use polars::prelude::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_sets() {
+        let old = df!(
+            "id" => &[1, 2, 3],
+            "status" => &["open", "closed", "open"],
+            "note" => &["a", "b", "c"]
+        )
+        .unwrap();
+        let new = df!(
+            "id" => &[1, 2, 30],
+            "status" => &["open", "pending", "open"],
+            "amount" => &[1.5, 2.5, 3.5]
+        )
+        .unwrap();
+
+        let old_set = ConstraintSet::new(&old, "test");
+        assert!(old_set.diff(&old_set).is_empty());
+
+        let diff = old_set.diff(&ConstraintSet::new(&new, "test"));
+        assert_eq!(diff.added_columns, vec!["amount"]);
+        assert_eq!(diff.removed_columns, vec!["note"]);
+
+        let id = &diff.changed_columns[0];
+        let max_value = id.changes.iter().find(|c| c.field == "max_value").unwrap();
+        assert_eq!((&max_value.old, &max_value.new), (&json!(3.0), &json!(30.0)));
+        let status = &diff.changed_columns[1];
+        assert_eq!((status.added_values.clone(), status.removed_values.clone()), (vec![String::from("pending")], vec![String::from("closed")]));
+        assert!(diff.to_string().contains("~ id: max_value: 3.0 -> 30.0\n"));
+
+        let mut newer = ConstraintSet::new(&old, "test");
+        newer.modify("id", "repairs.clamp", "true");
+        newer.modify("id", "mean", "1,4");
+        let changes: Vec<String> = old_set.diff(&newer).changed_columns[0].changes.iter().map(|c| c.to_string()).collect();
+        assert!(changes.contains(&String::from("aggregates.mean.min: null -> 1.0")), "{:?}", changes);
+        assert!(changes.contains(&String::from("aggregates.mean.max: null -> 4.0")));
+        assert!(changes.contains(&String::from("repairs.clamp: false -> true")));
+    }
+}
@@ -0,0 +1,375 @@
+pub mod generate {
+    use crate::constraints::constraints::*;
+    use polars::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashSet;
+
+    /// Share of nulls in nullable columns when the profile doesn't record it.
+    const DEFAULT_NULL_RATE: f64 = 0.05;
+    /// Draws of a value or column before giving up on a constraint.
+    const MAX_ATTEMPTS: usize = 100;
+
+    /// Share of nulls implied by a `null_count` aggregate range and the
+    /// profiled row count. The range isn't profiled, so without one set by
+    /// hand nullable columns get `DEFAULT_NULL_RATE`.
+    fn _null_rate(constraint: &Constraint, profiled_rows: Option<u32>) -> f64 {
+        if !constraint.nullable {
+            return 0.0;
+        }
+        match (&constraint.aggregates.null_count, profiled_rows) {
+            (Some(range), Some(rows)) if rows > 0 => ((range.min + range.max) / 2.0 / rows as f64).clamp(0.0, 1.0),
+            _ => DEFAULT_NULL_RATE,
+        }
+    }
+
+    /// Rounds a value up or down to a multiple of `step`, with the chance of
+    /// rounding up given by the remainder, so the mean is kept.
+    fn _round_to(value: f64, step: f64, rng: &mut StdRng) -> f64 {
+        let steps = value / step;
+        let up = rng.gen_bool((steps - steps.floor()).clamp(0.0, 1.0));
+        (if up { steps.ceil() } else { steps.floor() }) * step
+    }
+
+    /// Rounds a value to the step, sign, scale and precision the constraint
+    /// asks for.
+    fn _round(constraint: &Constraint, value: f64, lower: f64, rng: &mut StdRng) -> f64 {
+        let value = match constraint.multiple_of {
+            Some(step) => {
+                let rounded = _round_to(value, step, rng);
+                if rounded < lower { rounded + step } else { rounded }
+            }
+            None if constraint.integer_only || constraint.data_type.is_integer() => {
+                let rounded = _round_to(value, 1.0, rng);
+                if rounded < lower { rounded + 1.0 } else { rounded }
+            }
+            None => value,
+        };
+        let integer_digits = value.abs().trunc().to_string().trim_start_matches('0').len() as u32;
+        let decimals = match (constraint.scale, constraint.precision) {
+            (Some(scale), Some(precision)) => Some(scale.min(precision.saturating_sub(integer_digits))),
+            (scale, None) => scale,
+            (None, Some(precision)) => Some(precision.saturating_sub(integer_digits)),
+        };
+        match decimals {
+            Some(decimals) => {
+                let factor = 10_f64.powi(decimals as i32);
+                (value * factor).round() / factor
+            }
+            None => value,
+        }
+    }
+
+    /// Value at a random quantile, interpolated between the bounds and the
+    /// recorded quartiles, so skewed columns keep their median.
+    fn _quantile_sample(stats: &ColumnStats, lower: f64, upper: f64, rng: &mut StdRng) -> f64 {
+        let mut knots = [lower, stats.q1, stats.median, stats.q3, upper];
+        for i in 1..knots.len() {
+            knots[i] = knots[i].clamp(knots[i - 1], upper);
+        }
+        let position = rng.gen_range(0.0..4.0);
+        let i = (position as usize).min(3);
+        knots[i] + (knots[i + 1] - knots[i]) * (position - i as f64)
+    }
+
+    /// Shifts and scales the values towards the recorded mean and standard
+    /// deviation, keeping them within the bounds.
+    fn _match_moments(values: &mut [f64], stats: &ColumnStats, lower: f64, upper: f64) {
+        if values.len() < 2 {
+            return;
+        }
+        for _ in 0..100 {
+            let count = values.len() as f64;
+            let mean = values.iter().sum::<f64>() / count;
+            let std_dev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (count - 1.0)).sqrt();
+            let scale = if std_dev > 0.0 { stats.std_dev / std_dev } else { 1.0 };
+            for value in values.iter_mut() {
+                *value = (stats.mean + (*value - mean) * scale).clamp(lower, upper);
+            }
+        }
+    }
+
+    fn _numbers(constraint: &Constraint, rows: usize, rng: &mut StdRng) -> PolarsResult<Vec<f64>> {
+        let stats = constraint.stats.as_ref();
+        let mut lower = constraint
+            .min_value
+            .or(stats.map(|s| s.mean - 3.0 * s.std_dev))
+            .unwrap_or(0.0);
+        if constraint.positive || constraint.non_negative {
+            lower = lower.max(0.0);
+        }
+        if constraint.positive && lower == 0.0 {
+            lower = match constraint.multiple_of {
+                Some(step) => step,
+                None if constraint.integer_only || constraint.data_type.is_integer() => 1.0,
+                None => f64::EPSILON,
+            };
+        }
+        let upper = constraint
+            .max_value
+            .or(stats.map(|s| s.mean + 3.0 * s.std_dev))
+            .unwrap_or(lower + 100.0)
+            .max(lower);
+
+        let is_sequence = constraint.no_gaps
+            || (constraint.unique && (constraint.integer_only || constraint.data_type.is_integer()))
+            || matches!(
+                constraint.monotonic,
+                Some(Monotonic::StrictlyIncreasing) | Some(Monotonic::StrictlyDecreasing)
+            );
+        let mut values: Vec<f64> = if is_sequence {
+            let step = constraint.multiple_of.unwrap_or(if constraint.no_gaps { 1.0 } else {
+                ((upper - lower) / rows.max(1) as f64).max(1.0).floor()
+            });
+            let start = _round(constraint, lower, lower, rng);
+            if let Some(max) = constraint.max_value {
+                let span = if start > max { 0.0 } else { ((max - start) / step).floor() + 1.0 };
+                if (rows as f64) > span {
+                    return Err(PolarsError::ComputeError(
+                        format!("Could not generate {} unique values of {}, only {} fit its bounds", rows, constraint.name, span).into(),
+                    ));
+                }
+            }
+            let mut values: Vec<f64> = (0..rows).map(|i| start + i as f64 * step).collect();
+            if constraint.monotonic.is_none() {
+                values.shuffle(rng);
+            }
+            values
+        } else {
+            let mut values: Vec<f64> = (0..rows)
+                .map(|_| match stats {
+                    Some(stats) => _quantile_sample(stats, lower, upper, rng),
+                    None if upper > lower => rng.gen_range(lower..=upper),
+                    None => lower,
+                })
+                .collect();
+            if let Some(stats) = stats {
+                _match_moments(&mut values, stats, lower, upper);
+            }
+            values
+                .into_iter()
+                .map(|value| _round(constraint, value, lower, rng).min(upper))
+                .collect()
+        };
+        match constraint.monotonic {
+            Some(Monotonic::Increasing) | Some(Monotonic::StrictlyIncreasing) => {
+                values.sort_by(|a, b| a.total_cmp(b))
+            }
+            Some(Monotonic::Decreasing) | Some(Monotonic::StrictlyDecreasing) => {
+                values.sort_by(|a, b| b.total_cmp(a))
+            }
+            None => {}
+        }
+        Ok(values)
+    }
+
+    /// Random text within the length bounds and case of the constraint.
+    fn _text(constraint: &Constraint, rng: &mut StdRng) -> String {
+        let min = constraint.min_length.unwrap_or(1) as usize;
+        let max = (constraint.max_length.unwrap_or(10) as usize).max(min);
+        let length = rng.gen_range(min..=max);
+        let text: String = (0..length).map(|_| rng.gen_range(b'a'..=b'z') as char).collect();
+        match constraint.case {
+            Some(Case::Upper) => text.to_uppercase(),
+            Some(Case::Title) => {
+                let mut chars = text.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            _ => text,
+        }
+    }
+
+    fn _strings(constraint: &Constraint, rows: usize, rng: &mut StdRng) -> PolarsResult<Vec<String>> {
+        let mut members: Vec<String> = constraint
+            .value_range
+            .as_deref()
+            .map(|range| range.split(", ").filter(|m| *m != "null").map(String::from).collect())
+            .unwrap_or_default();
+        if constraint.unique && !members.is_empty() && members.len() < rows {
+            return Err(PolarsError::ComputeError(
+                format!("Could not generate {} unique values of {}, its value_range has {}", rows, constraint.name, members.len()).into(),
+            ));
+        }
+        if !members.is_empty() {
+            if constraint.unique {
+                members.shuffle(rng);
+                members.truncate(rows);
+                return Ok(members);
+            }
+            return Ok((0..rows).map(|_| members.choose(rng).cloned().unwrap_or_default()).collect());
+        }
+
+        if let Some(semantic_type) = constraint.semantic_type {
+            let offset: u64 = rng.gen_range(0..1_000_000);
+            return Ok((0..rows)
+                .map(|i| {
+                    let n = if constraint.unique { offset + i as u64 } else { rng.gen() };
+                    semantic_type.example(n)
+                })
+                .collect());
+        }
+
+        let mut seen: HashSet<String> = HashSet::new();
+        (0..rows)
+            .map(|_| match &constraint.suggested_type {
+                Some(ColumnType::Bool) => Ok(String::from(if rng.gen() { "true" } else { "false" })),
+                Some(t) if t.is_integer() => Ok(rng.gen_range(0..1000).to_string()),
+                Some(t) if t.is_float() => Ok(format!("{:.2}", rng.gen_range(0.0..1000.0))),
+                Some(ColumnType::Date) => Ok(_date(rng)),
+                _ if constraint.data_type.is_temporal() => Ok(_date(rng)),
+                _ => {
+                    for _ in 0..MAX_ATTEMPTS {
+                        let text = _text(constraint, rng);
+                        if !constraint.unique || seen.insert(text.clone()) {
+                            return Ok(text);
+                        }
+                    }
+                    Err(PolarsError::ComputeError(
+                        format!("Could not generate {} unique values of {}", rows, constraint.name).into(),
+                    ))
+                }
+            })
+            .collect()
+    }
+
+    fn _date(rng: &mut StdRng) -> String {
+        let start = chrono::NaiveDate::from_ymd_opt(2020, 1, 1).unwrap_or_default();
+        (start + chrono::Duration::days(rng.gen_range(0..1500))).to_string()
+    }
+
+    /// Whether every aggregate of the column falls in its range.
+    fn _fits_aggregates(constraint: &Constraint, col: &Series) -> bool {
+        AGGREGATES.iter().all(|name| match constraint.aggregates.range(name) {
+            Some(range) => aggregate(col, name).is_some_and(|v| range.contains(v)),
+            None => true,
+        })
+    }
+
+    /// Draws the column until its aggregates fall in their ranges.
+    fn _column(constraint: &Constraint, rows: usize, null_rate: f64, rng: &mut StdRng) -> PolarsResult<Series> {
+        for _ in 0..MAX_ATTEMPTS {
+            let col = _draw_column(constraint, rows, null_rate, rng)?;
+            if _fits_aggregates(constraint, &col) {
+                return Ok(col);
+            }
+        }
+        Err(PolarsError::ComputeError(
+            format!("Could not generate values of {} within its aggregate ranges", constraint.name).into(),
+        ))
+    }
+
+    fn _draw_column(constraint: &Constraint, rows: usize, null_rate: f64, rng: &mut StdRng) -> PolarsResult<Series> {
+        let name = &constraint.name;
+        let is_null = |rng: &mut StdRng| null_rate > 0.0 && rng.gen_bool(null_rate);
+        if constraint.data_type.is_numeric() {
+            let values: Vec<Option<f64>> = _numbers(constraint, rows, rng)?
+                .into_iter()
+                .map(|v| (!is_null(rng)).then_some(v))
+                .collect();
+            let dtype = constraint.data_type.to_dtype().unwrap_or(DataType::Float64);
+            Series::new(name, values).cast(&dtype)
+        } else if constraint.data_type == ColumnType::Bool {
+            let values: Vec<Option<bool>> = (0..rows).map(|_| (!is_null(rng)).then(|| rng.gen())).collect();
+            Ok(Series::new(name, values))
+        } else {
+            let values: Vec<Option<String>> = _strings(constraint, rows, rng)?
+                .into_iter()
+                .map(|v| (!is_null(rng)).then_some(v))
+                .collect();
+            Ok(Series::new(name, values))
+        }
+    }
+
+    impl ConstraintSet {
+        /// Generates `rows` rows of synthetic data satisfying the
+        /// constraints. Numeric columns follow the recorded mean and standard
+        /// deviation, and nullable columns the share of nulls given by their
+        /// `null_count` range, with columns redrawn until they fall in their
+        /// aggregate ranges. Fails when unique or aggregate constraints can't
+        /// be met, e.g. when a unique column has fewer possible values than
+        /// `rows`, and redraws the rows until none are duplicated if the
+        /// table forbids it. The same seed always gives the same data.
+        pub fn generate(&self, rows: usize, seed: u64) -> PolarsResult<DataFrame> {
+            let mut rng = StdRng::seed_from_u64(seed);
+            for _ in 0..MAX_ATTEMPTS {
+                let columns = self
+                    .set
+                    .iter()
+                    .map(|c| _column(c, rows, _null_rate(c, self.table.profiled_rows), &mut rng))
+                    .collect::<PolarsResult<Vec<Series>>>()?;
+                let data = DataFrame::new(columns)?;
+                match &self.table.duplicate_rows {
+                    Some(check) if !check.find(&data).unwrap_or_default().is_empty() => continue,
+                    _ => return Ok(data),
+                }
+            }
+            Err(PolarsError::ComputeError(
+                format!("Could not generate {} rows without duplicates", rows).into(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constraints::constraints::*;
+    use crate::validation::validation::ValidationSet;
+    use polars::prelude::*;
+
+    #[test]
+    fn generate_rows() {
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let options = InferenceOptions {
+            aggregate_tolerance: Some(0.1),
+            ..Default::default()
+        };
+        let set = ConstraintSet::infer(&df, "test", &options);
+
+        let generated = set.generate(500, 7).unwrap();
+        assert_eq!(generated.shape(), (500, 10));
+        assert_eq!(generated.dtypes(), df.dtypes());
+        assert!(set.row_findings(&generated).unwrap().iter().all(|row| row.is_empty()));
+        let validation = ValidationSet::new(&generated, &set);
+        assert!(!validation.has_errors(), "{:?}", validation.findings);
+        assert!(generated.frame_equal_missing(&set.generate(500, 7).unwrap()));
+        assert!(!generated.frame_equal_missing(&set.generate(500, 8).unwrap()));
+
+        let df = df!(
+            "invoice" => &[100, 101, 102, 103],
+            "email" => &["a@example.com", "b@example.org", "c@example.net", "d@example.com"]
+        )
+        .unwrap();
+        let mut set = ConstraintSet::new(&df, "test");
+        set.set[1].value_range = None;
+        set.set[1].max_length = None;
+        let generated = set.generate(4, 1).unwrap();
+        assert!(set.row_findings(&generated).unwrap().iter().all(|row| row.is_empty()));
+        let invoices: Vec<Option<i32>> = generated.column("invoice").unwrap().i32().unwrap().into_iter().collect();
+        assert_eq!(invoices, vec![Some(100), Some(101), Some(102), Some(103)]);
+
+        let df = df!("code" => &["a", "b", "c"]).unwrap();
+        let mut set = ConstraintSet::new(&df, "test");
+        set.set[0].value_range = None;
+        assert!(set.generate(50, 1).is_err());
+
+        let df = df!("id" => &[1, 2, 3, 4], "code" => &["a", "b", "c", "d"]).unwrap();
+        let mut set = ConstraintSet::new(&df, "test");
+        assert!(set.generate(4, 1).is_ok());
+        assert!(set.generate(10, 1).is_err());
+        set.set[0].unique = false;
+        set.set[0].no_gaps = false;
+        set.set[0].monotonic = None;
+        assert!(set.generate(10, 1).is_err());
+        set.set[1].unique = false;
+        let generated = set.generate(10, 1).unwrap();
+        let validation = ValidationSet::new(&generated, &set);
+        assert!(!validation.has_errors(), "{:?}", validation.findings);
+    }
+}
@@ -0,0 +1,24 @@
+#[allow(clippy::module_inception)]
+pub mod constraints;
+#[allow(clippy::module_inception)]
+pub mod validation;
+#[allow(clippy::module_inception)]
+pub mod schema;
+#[allow(clippy::module_inception)]
+pub mod sql;
+#[allow(clippy::module_inception)]
+pub mod types;
+#[allow(clippy::module_inception)]
+pub mod semantic;
+#[allow(clippy::module_inception)]
+pub mod split;
+#[allow(clippy::module_inception)]
+pub mod repair;
+#[allow(clippy::module_inception)]
+pub mod generate;
+#[allow(clippy::module_inception)]
+pub mod mutate;
+#[allow(clippy::module_inception)]
+pub mod diff;
+#[allow(clippy::module_inception)]
+pub mod compare;
@@ -1,9 +1,186 @@
-mod constraints;
-mod validation;
-use clap::{arg, Command};
-use constraints::constraints::*;
+use clap::{arg, ArgMatches, Command};
+use dvrs::compare::compare::compare as compare_profiles;
+use dvrs::constraints::constraints::*;
+use dvrs::repair::repair::save_audit_log;
+use dvrs::split::split::write_frame;
+use dvrs::sql::sql::SqlDialect;
+use dvrs::validation::validation::*;
 use polars::prelude::*;
-use validation::validation::*;
+use std::path::Path;
+
+fn export_sql(matches: &ArgMatches) {
+    let constraints_path = matches.get_one::<String>("constraints").unwrap();
+    let dialect: SqlDialect = matches.get_one::<String>("dialect").unwrap().parse().unwrap();
+    let cons = ConstraintSet::read_constraints(constraints_path).unwrap();
+    let table = match matches.get_one::<String>("table") {
+        Some(table) => table.to_string(),
+        None => Path::new(constraints_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| cons.name.clone()),
+    };
+
+    let ddl = cons.to_sql(&table, dialect);
+    match matches.get_one::<String>("output") {
+        Some(output_path) => {
+            std::fs::write(output_path, ddl).unwrap();
+            println!("SQL saved at: {}", output_path)
+        }
+        None => print!("{}", ddl),
+    }
+}
+
+fn import(matches: &ArgMatches) {
+    let schema_path = matches.get_one::<String>("schema").unwrap();
+    let output_path = matches.get_one::<String>("output").unwrap();
+    let cons = ConstraintSet::read_schema(schema_path).unwrap();
+    cons.save(output_path).unwrap();
+    println!("Constraints saved at: {}", output_path)
+}
+
+fn validate(matches: &ArgMatches) {
+    let file_path = matches.get_one::<String>("file").unwrap();
+    let constraints_path = matches.get_one::<String>("constraints").unwrap();
+    let cons = ConstraintSet::read_constraints(constraints_path).unwrap();
+    let data = cons.read_csv(file_path).unwrap();
+
+    let val = ValidationSet::for_file(&data, &cons, file_path);
+    println!("{}", val);
+
+    if let Some(mut paths) = matches.get_many::<String>("split") {
+        let clean_path = paths.next().unwrap();
+        let rejected_path = paths.next().unwrap();
+        let (mut clean, mut rejected) = cons.split(&data).unwrap();
+        write_frame(&mut clean, clean_path).unwrap();
+        write_frame(&mut rejected, rejected_path).unwrap();
+        println!("{} clean rows saved at: {}", clean.height(), clean_path);
+        println!("{} rejected rows saved at: {}", rejected.height(), rejected_path);
+    }
+
+    if val.has_errors() {
+        std::process::exit(1);
+    }
+}
+
+/// Parses --quantiles as LOWER,UPPER, rejecting values outside 0..=1.
+fn parse_quantiles(value: &str) -> Result<(f64, f64), String> {
+    let bounds = value
+        .split(',')
+        .map(|q| q.trim().parse::<f64>().map_err(|_| format!("{} is not a number", q.trim())))
+        .collect::<Result<Vec<f64>, String>>()?;
+    let [lower, upper] = bounds[..] else {
+        return Err(String::from("Quantiles must be given as LOWER,UPPER"));
+    };
+    BoundsStrategy::quantile(lower, upper, 0.0).map(|_| (lower, upper))
+}
+
+/// Parses --rate, rejecting shares outside 0..=1.
+fn parse_rate(value: &str) -> Result<f64, String> {
+    let rate: f64 = value.trim().parse().map_err(|_| format!("{} is not a number", value.trim()))?;
+    if !(0.0..=1.0).contains(&rate) {
+        return Err(format!("Rate must be between 0 and 1, got {}", rate));
+    }
+    Ok(rate)
+}
+
+fn fix(matches: &ArgMatches) {
+    let file_path = matches.get_one::<String>("file").unwrap();
+    let constraints_path = matches.get_one::<String>("constraints").unwrap();
+    let output_path = matches.get_one::<String>("output").unwrap();
+    let log_path = match matches.get_one::<String>("log") {
+        Some(path) => path.to_string(),
+        None => Path::new(output_path)
+            .with_extension("audit.csv")
+            .to_string_lossy()
+            .to_string(),
+    };
+    let cons = ConstraintSet::read_constraints(constraints_path).unwrap();
+    let data = cons.read_csv(file_path).unwrap();
+
+    let (mut repaired, changes) = cons.repair(&data).unwrap();
+    write_frame(&mut repaired, output_path).unwrap();
+    save_audit_log(&changes, &log_path).unwrap();
+    println!("Repaired file saved at: {}", output_path);
+    println!("{} changes logged at: {}", changes.len(), log_path)
+}
+
+fn generate(matches: &ArgMatches) {
+    let constraints_path = matches.get_one::<String>("constraints").unwrap();
+    let output_path = matches.get_one::<String>("output").unwrap();
+    let rows = *matches.get_one::<usize>("rows").unwrap();
+    let seed = matches.get_one::<u64>("seed").copied().unwrap_or_else(|| {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH);
+        now.map(|d| d.as_nanos() as u64).unwrap_or_default()
+    });
+    let cons = ConstraintSet::read_constraints(constraints_path).unwrap();
+
+    let mut data = cons.generate(rows, seed).unwrap();
+    write_frame(&mut data, output_path).unwrap();
+    println!("{} rows generated with seed {} saved at: {}", rows, seed, output_path)
+}
+
+fn mutate(matches: &ArgMatches) {
+    let file_path = matches.get_one::<String>("file").unwrap();
+    let constraints_path = matches.get_one::<String>("constraints").unwrap();
+    let output_path = matches.get_one::<String>("output").unwrap();
+    let rate = *matches.get_one::<f64>("rate").unwrap();
+    let seed = *matches.get_one::<u64>("seed").unwrap();
+    let manifest_path = match matches.get_one::<String>("manifest") {
+        Some(path) => path.to_string(),
+        None => Path::new(output_path)
+            .with_extension("manifest.json")
+            .to_string_lossy()
+            .to_string(),
+    };
+    let cons = ConstraintSet::read_constraints(constraints_path).unwrap();
+    let data = cons.read_csv(file_path).unwrap();
+
+    let (mut mutated, manifest) = cons.mutate(&data, rate, seed).unwrap();
+    write_frame(&mut mutated, output_path).unwrap();
+    manifest.save(&manifest_path).unwrap();
+    println!("Mutated file saved at: {}", output_path);
+    println!("{} violations listed at: {}", manifest.mutations.len(), manifest_path)
+}
+
+fn modify(matches: &ArgMatches) {
+    let constraints_path = matches.get_one::<String>("constraints").unwrap();
+    let column = matches.get_one::<String>("column").unwrap();
+    let constraint = matches.get_one::<String>("constraint").unwrap();
+    let value = matches.get_one::<String>("value").unwrap();
+    let mut cons = ConstraintSet::read_constraints(constraints_path).unwrap();
+
+    if column == "table" {
+        cons.modify_table(constraint, value);
+    } else {
+        cons.modify(column, constraint, value);
+    }
+    cons.save(constraints_path).unwrap();
+    println!("Constraints saved at: {}", constraints_path)
+}
+
+fn diff(matches: &ArgMatches) {
+    let old = ConstraintSet::read_constraints(matches.get_one::<String>("old").unwrap()).unwrap();
+    let new = ConstraintSet::read_constraints(matches.get_one::<String>("new").unwrap()).unwrap();
+    let diff = old.diff(&new);
+    if matches.get_flag("json") {
+        println!("{}", serde_json::to_string_pretty(&diff).unwrap())
+    } else {
+        print!("{}", diff)
+    }
+}
+
+fn compare(matches: &ArgMatches) {
+    let old_path = matches.get_one::<String>("old").unwrap();
+    let new_path = matches.get_one::<String>("new").unwrap();
+    let old: DataFrame = CsvReader::from_path(old_path).unwrap().finish().unwrap();
+    let new: DataFrame = CsvReader::from_path(new_path).unwrap().finish().unwrap();
+    let comparison = compare_profiles(&old, &new);
+    if matches.get_flag("json") {
+        println!("{}", serde_json::to_string_pretty(&comparison).unwrap())
+    } else {
+        print!("{}", comparison)
+    }
+}
 
 fn main() {
     let matches = Command::new("dvrs")
@@ -13,6 +190,7 @@ fn main() {
         .after_help(
             "To run this program provide a csv file (-f) for either validation or constraints generation"
         )
+        .subcommand_negates_reqs(true)
         .arg(arg!(-f --file <VALUE>).required(true).help("Csv file"))
         .arg(
             arg!(-c --constraints ...)
@@ -22,7 +200,69 @@ fn main() {
         .arg(
             arg!(-s --save <VALUE>)
                 .required(false)
-                .help("Save Constraints"),
+                .help("Save Constraints (json, yaml or toml by extension)"),
+        )
+        .arg(
+            arg!(--quantiles <VALUE>)
+                .required(false)
+                .value_parser(parse_quantiles)
+                .help("Infer min/max values from quantiles, e.g. 0.01,0.99"),
+        )
+        .arg(
+            arg!(--margin <VALUE>)
+                .required(false)
+                .default_value("0")
+                .value_parser(clap::value_parser!(f64))
+                .help("Widen quantile bounds by this fraction of their range"),
+        )
+        .arg(
+            arg!(--"z-score" <VALUE>)
+                .required(false)
+                .value_parser(clap::value_parser!(f64))
+                .help("Flag values this many standard deviations from the mean"),
+        )
+        .arg(
+            arg!(--iqr <VALUE>)
+                .required(false)
+                .value_parser(clap::value_parser!(f64))
+                .help("Flag values outside this multiple of the interquartile range"),
+        )
+        .arg(
+            arg!(--"aggregate-tolerance" <VALUE>)
+                .required(false)
+                .value_parser(clap::value_parser!(f64))
+                .help("Profile mean, median and standard deviation ranges this relatively wide"),
+        )
+        .arg(
+            arg!(--"name-matching" <VALUE>)
+                .required(false)
+                .value_parser(["exact", "insensitive"])
+                .help("Match columns to constraints by exact name or ignoring case and whitespace"),
+        )
+        .arg(
+            arg!(--name <VALUE>)
+                .required(false)
+                .help("Name of the constraint set (defaults to the file name)"),
+        )
+        .arg(
+            arg!(--description <VALUE>)
+                .required(false)
+                .help("Description of the constraint set"),
+        )
+        .arg(
+            arg!(--owner <VALUE>)
+                .required(false)
+                .help("Owner or contact of the data"),
+        )
+        .arg(
+            arg!(--"json-schema" <VALUE>)
+                .required(false)
+                .help("Save Constraints as JSON Schema"),
+        )
+        .arg(
+            arg!(--"table-schema" <VALUE>)
+                .required(false)
+                .help("Save Constraints as Frictionless Table Schema"),
         )
         .arg(
             arg!(-v --validate <VALUE>)
@@ -34,36 +274,230 @@ fn main() {
                 .required(false)
                 .help("Save validation to csv"),
         )
+        .subcommand(
+            Command::new("modify")
+                .about("Change a constraint of a column, or of the table, in a constraints file")
+                .arg(arg!(<constraints> "Constraints file"))
+                .arg(arg!(<column> "Column name, or table for table constraints"))
+                .arg(arg!(<constraint> "Constraint name, e.g. max_value or min_rows"))
+                .arg(arg!(<value> "New value, empty to remove the constraint")),
+        )
+        .subcommand(
+            Command::new("validate")
+                .about("Validate a csv file against a constraints file")
+                .arg(arg!(<file> "Csv file"))
+                .arg(arg!(<constraints> "Constraints file"))
+                .arg(
+                    arg!(--split <PATHS>)
+                        .required(false)
+                        .num_args(2)
+                        .value_names(["CLEAN", "REJECTED"])
+                        .help("Save passing and failing rows to csv or parquet files"),
+                ),
+        )
+        .subcommand(
+            Command::new("fix")
+                .about("Repair a csv file with the repairs set in a constraints file")
+                .arg(arg!(<file> "Csv file"))
+                .arg(arg!(<constraints> "Constraints file"))
+                .arg(
+                    arg!(-o --output <VALUE>)
+                        .required(true)
+                        .help("Save the repaired file (csv or parquet by extension)"),
+                )
+                .arg(
+                    arg!(--log <VALUE>)
+                        .required(false)
+                        .help("Save the audit log (defaults to <output>.audit.csv)"),
+                ),
+        )
+        .subcommand(
+            Command::new("generate")
+                .about("Generate synthetic data satisfying a constraints file")
+                .arg(arg!(<constraints> "Constraints file"))
+                .arg(
+                    arg!(-n --rows <VALUE>)
+                        .required(false)
+                        .default_value("100")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Number of rows"),
+                )
+                .arg(
+                    arg!(--seed <VALUE>)
+                        .required(false)
+                        .value_parser(clap::value_parser!(u64))
+                        .help("Random seed, for reproducible data"),
+                )
+                .arg(
+                    arg!(-o --output <VALUE>)
+                        .required(true)
+                        .help("Save the data (csv or parquet by extension)"),
+                ),
+        )
+        .subcommand(
+            Command::new("mutate")
+                .about("Inject violations of a constraints file into a valid csv file")
+                .arg(arg!(<file> "Csv file"))
+                .arg(arg!(<constraints> "Constraints file"))
+                .arg(
+                    arg!(--rate <VALUE>)
+                        .required(false)
+                        .default_value("0.01")
+                        .value_parser(parse_rate)
+                        .help("Share of rows to corrupt"),
+                )
+                .arg(
+                    arg!(--seed <VALUE>)
+                        .required(false)
+                        .default_value("0")
+                        .value_parser(clap::value_parser!(u64))
+                        .help("Random seed"),
+                )
+                .arg(
+                    arg!(-o --output <VALUE>)
+                        .required(true)
+                        .help("Save the mutated file (csv or parquet by extension)"),
+                )
+                .arg(
+                    arg!(--manifest <VALUE>)
+                        .required(false)
+                        .help("Save the expected violations (defaults to <output>.manifest.json)"),
+                ),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Compare two constraints files")
+                .arg(arg!(<old> "Old constraints file"))
+                .arg(arg!(<new> "New constraints file"))
+                .arg(arg!(--json "Print the differences as JSON")),
+        )
+        .subcommand(
+            Command::new("compare")
+                .about("Compare the profiles of two data files")
+                .arg(arg!(<old> "Old csv file"))
+                .arg(arg!(<new> "New csv file"))
+                .arg(arg!(--json "Print the differences as JSON")),
+        )
+        .subcommand(
+            Command::new("export-sql")
+                .about("Generate a CREATE TABLE statement from a constraints file")
+                .arg(arg!(<constraints> "Constraints file"))
+                .arg(
+                    arg!(-d --dialect <VALUE>)
+                        .required(false)
+                        .default_value("postgres")
+                        .value_parser(["postgres", "sqlite", "mysql"])
+                        .help("SQL dialect"),
+                )
+                .arg(
+                    arg!(-t --table <VALUE>)
+                        .required(false)
+                        .help("Table name (defaults to the constraints file name)"),
+                )
+                .arg(
+                    arg!(-o --output <VALUE>)
+                        .required(false)
+                        .help("Save SQL to file"),
+                ),
+        )
+        .subcommand(
+            Command::new("import")
+                .about("Convert a JSON Schema or Table Schema to a constraints file")
+                .arg(arg!(<schema> "JSON Schema or Table Schema file"))
+                .arg(
+                    arg!(-o --output <VALUE>)
+                        .required(true)
+                        .help("Save constraints to file"),
+                ),
+        )
         .get_matches();
 
+    match matches.subcommand() {
+        Some(("export-sql", sub_matches)) => return export_sql(sub_matches),
+        Some(("import", sub_matches)) => return import(sub_matches),
+        Some(("validate", sub_matches)) => return validate(sub_matches),
+        Some(("fix", sub_matches)) => return fix(sub_matches),
+        Some(("generate", sub_matches)) => return generate(sub_matches),
+        Some(("mutate", sub_matches)) => return mutate(sub_matches),
+        Some(("modify", sub_matches)) => return modify(sub_matches),
+        Some(("diff", sub_matches)) => return diff(sub_matches),
+        Some(("compare", sub_matches)) => return compare(sub_matches),
+        _ => {}
+    }
+
     let file_path = matches.get_one::<String>("file").expect("File is required");
     let print_constraint = matches.get_one::<u8>("constraints").unwrap();
     let save_constraint = matches.get_one::<String>("save");
+    let json_schema = matches.get_one::<String>("json-schema");
+    let table_schema = matches.get_one::<String>("table-schema");
     let validate = matches.get_one::<String>("validate");
     let output = matches.get_one::<String>("output");
 
-    let data: DataFrame = CsvReader::from_path(&file_path).unwrap().finish().unwrap();
+    let data: DataFrame = CsvReader::from_path(file_path).unwrap().finish().unwrap();
+
+    let mut options = InferenceOptions {
+        z_score: matches.get_one::<f64>("z-score").copied(),
+        iqr_factor: matches.get_one::<f64>("iqr").copied(),
+        aggregate_tolerance: matches.get_one::<f64>("aggregate-tolerance").copied(),
+        ..Default::default()
+    };
+    if let Some((lower, upper)) = matches.get_one::<(f64, f64)>("quantiles") {
+        let margin = *matches.get_one::<f64>("margin").unwrap();
+        options.bounds = BoundsStrategy::quantile(*lower, *upper, margin).unwrap();
+    }
+
+    // Profiled once, so every output shares the same hash and timestamp.
+    let needs_profile = *print_constraint == 1 || save_constraint.is_some() || json_schema.is_some() || table_schema.is_some();
+    let cons = needs_profile.then(|| {
+        let mut cons = ConstraintSet::for_file(&data, &options, file_path);
+        if let Some(name) = matches.get_one::<String>("name") {
+            cons.name = name.to_string();
+        }
+        cons.metadata.description = matches.get_one::<String>("description").cloned();
+        cons.metadata.owner = matches.get_one::<String>("owner").cloned();
+        if let Some(matching) = matches.get_one::<String>("name-matching") {
+            cons.modify_table("name_matching", matching);
+        }
+        cons
+    });
+
+    if let (1, Some(cons)) = (*print_constraint, &cons) {
+        println!("{}", cons)
+    }
 
-    if print_constraint.to_owned() == 1 {
-        println!("{}", ConstraintSet::new(&data))
+    if let (Some(save_path), Some(cons)) = (save_constraint, &cons) {
+        cons.save(save_path).unwrap();
+        println!("Constraints saved at: {}", save_path)
     }
 
-    if save_constraint.is_some() {
-        let cons = ConstraintSet::new(&data);
-        cons.save_json(save_constraint.unwrap()).unwrap();
-        println!("Constraints saved at: {}", save_constraint.unwrap())
+    if let (Some(schema_path), Some(cons)) = (json_schema, &cons) {
+        cons.save_json_schema(schema_path).unwrap();
+        println!("JSON Schema saved at: {}", schema_path)
     }
 
-    if validate.is_some() {
-        let cons = ConstraintSet::read_constraints(&validate.unwrap());
-        let val = ValidationSet::new(&data, &cons.unwrap());
+    if let (Some(schema_path), Some(cons)) = (table_schema, &cons) {
+        cons.save_table_schema(schema_path).unwrap();
+        println!("Table Schema saved at: {}", schema_path)
+    }
+
+    let mut has_errors = false;
+
+    if let Some(constraints_path) = validate {
+        let cons = ConstraintSet::read_constraints(constraints_path).unwrap();
+        let val = ValidationSet::for_file(&cons.read_csv(file_path).unwrap(), &cons, file_path);
+        has_errors = val.has_errors();
         println!("{}", val)
     }
 
-    if output.is_some() {
-        let cons = ConstraintSet::read_constraints(&validate.unwrap());
-        let val = ValidationSet::new(&data, &cons.unwrap());
-        val.save_csv(&output.unwrap()).unwrap();
-        println!("Validations saved at: {}", output.unwrap())
+    if let Some(output_path) = output {
+        let cons = ConstraintSet::read_constraints(validate.expect("Validation requires -v")).unwrap();
+        let val = ValidationSet::for_file(&cons.read_csv(file_path).unwrap(), &cons, file_path);
+        val.save_csv(output_path).unwrap();
+        println!("Validations saved at: {}", output_path)
+    }
+
+    // Warnings and info findings are reported but don't fail the run.
+    if has_errors {
+        std::process::exit(1);
     }
 }
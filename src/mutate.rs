@@ -0,0 +1,310 @@
+pub mod mutate {
+    use crate::constraints::constraints::*;
+    use polars::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+    use std::fs::File;
+    use std::io::Write;
+
+    /// A violation injected into one row.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Mutation {
+        /// Line number in the output file, counting the header as line 1.
+        pub line: usize,
+        /// Column name, or `table` for duplicated rows.
+        pub column: String,
+        pub check: String,
+    }
+
+    /// What a mutation run injected, to compare with a validation of the
+    /// mutated file. Counts are for the targeted check only: an injected
+    /// value can also fail other checks, e.g. a duplicated row also fails
+    /// the `unique` columns. A repeated value counts twice under `unique`,
+    /// as validation flags both rows holding it.
+    #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+    pub struct Manifest {
+        pub seed: u64,
+        pub rate: f64,
+        pub rows: usize,
+        /// Injected violations by column and check.
+        pub expected: BTreeMap<String, BTreeMap<String, u32>>,
+        pub mutations: Vec<Mutation>,
+    }
+
+    impl Manifest {
+        pub fn save(&self, filepath: &str) -> Result<(), String> {
+            let json = serde_json::to_string_pretty(self).map_err(|err| err.to_string())?;
+            let mut file = File::create(filepath).map_err(|err| err.to_string())?;
+            file.write_all(json.as_bytes()).map_err(|err| err.to_string())
+        }
+    }
+
+    /// Values of a column that mutations can edit.
+    enum Cells {
+        Numbers(Vec<Option<f64>>),
+        Texts(Vec<Option<String>>),
+    }
+
+    impl Cells {
+        fn is_null(&self, row: usize) -> bool {
+            match self {
+                Cells::Numbers(values) => values[row].is_none(),
+                Cells::Texts(values) => values[row].is_none(),
+            }
+        }
+    }
+
+    fn _cells(col: &Series) -> Option<Cells> {
+        if col.dtype().is_numeric() {
+            let values = col.cast(&DataType::Float64).ok()?.f64().ok()?.into_iter().collect();
+            Some(Cells::Numbers(values))
+        } else if col.dtype() == &DataType::Utf8 {
+            let values = col.utf8().ok()?.into_iter().map(|v| v.map(String::from)).collect();
+            Some(Cells::Texts(values))
+        } else {
+            None
+        }
+    }
+
+    /// Checks of a constraint that a value of the column can be made to fail.
+    /// `unique` needs a previous row to copy that isn't null.
+    fn _checks(constraint: &Constraint, cells: &Cells, copy_from: Option<usize>) -> Vec<&'static str> {
+        let mut checks = vec![];
+        if !constraint.nullable {
+            checks.push("nullable");
+        }
+        if constraint.unique && copy_from.is_some_and(|row| !cells.is_null(row)) {
+            checks.push("unique");
+        }
+        match cells {
+            Cells::Numbers(_) => {
+                if constraint.min_value.is_some() {
+                    checks.push("min_value");
+                }
+                if constraint.max_value.is_some() {
+                    checks.push("max_value");
+                }
+            }
+            Cells::Texts(_) => {
+                if constraint.value_range.is_some() {
+                    checks.push("value_range");
+                }
+                if constraint.max_length.is_some() {
+                    checks.push("max_length");
+                }
+            }
+        }
+        checks
+    }
+
+    /// A value outside the bounds, by the width of the range or at least 1
+    /// so integer columns stay integers, plus the row number so values
+    /// injected into a unique column don't repeat.
+    fn _out_of_range(constraint: &Constraint, below: bool, row: usize) -> Option<f64> {
+        let min = constraint.min_value;
+        let max = constraint.max_value;
+        let width = match (min, max) {
+            (Some(min), Some(max)) => (max - min).abs().ceil().max(1.0),
+            _ => 1.0,
+        } + row as f64;
+        if below {
+            min.map(|min| min.floor() - width)
+        } else {
+            max.map(|max| max.ceil() + width)
+        }
+    }
+
+    /// A category missing from the value range, within the length bounds.
+    fn _bad_category(constraint: &Constraint) -> String {
+        let members: Vec<&str> = constraint.value_range.as_deref().unwrap_or_default().split(", ").collect();
+        let length = constraint.min_length.unwrap_or(1).max(1) as usize;
+        ['x', 'z', 'q']
+            .iter()
+            .map(|c| c.to_string().repeat(length))
+            .find(|v| !members.contains(&v.as_str()))
+            .unwrap_or_else(|| "x".repeat(length + 1))
+    }
+
+    fn _mutate_cell(constraint: &Constraint, cells: &mut Cells, row: usize, check: &str) {
+        match cells {
+            Cells::Numbers(values) => {
+                values[row] = match check {
+                    "nullable" => None,
+                    "unique" => values[row - 1],
+                    "min_value" => _out_of_range(constraint, true, row),
+                    "max_value" => _out_of_range(constraint, false, row),
+                    _ => values[row],
+                }
+            }
+            Cells::Texts(values) => {
+                values[row] = match check {
+                    "nullable" => None,
+                    "unique" => values[row - 1].clone(),
+                    "value_range" => Some(_bad_category(constraint)),
+                    "max_length" => {
+                        let max = constraint.max_length.unwrap_or_default() as usize;
+                        let value = values[row].clone().unwrap_or_default();
+                        Some(format!("{}{}", value, "x".repeat((max + 1).saturating_sub(value.chars().count()))))
+                    }
+                    _ => values[row].clone(),
+                }
+            }
+        }
+    }
+
+    impl ConstraintSet {
+        /// Injects violations into `rate` of the rows of valid data. Each
+        /// chosen row gets one violation of a random check: a null, a value
+        /// out of bounds, a bad category, an over-length string, a repeated
+        /// value in a unique column or a copy of the previous row. Rows
+        /// next to a mutated row aren't copied, so each violation is counted
+        /// once. Columns matched through an alias or ignoring case are
+        /// mutated too, and listed under their constraint name. Fails when
+        /// `rate` is outside `0..=1`. The same seed always gives the same
+        /// mutations.
+        pub fn mutate(&self, data: &DataFrame, rate: f64, seed: u64) -> PolarsResult<(DataFrame, Manifest)> {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(PolarsError::ComputeError(format!("Rate must be between 0 and 1, got {}", rate).into()));
+            }
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut manifest = Manifest {
+                seed,
+                rate,
+                rows: data.height(),
+                ..Default::default()
+            };
+            let mut columns: Vec<(&Constraint, String, Cells)> = self
+                .set
+                .iter()
+                .zip(self.match_columns(data))
+                .filter_map(|(c, matched)| {
+                    let name = matched?;
+                    let cells = _cells(data.column(&name).ok()?)?;
+                    Some((c, name, cells))
+                })
+                .collect();
+
+            let mut mutated = vec![false; data.height()];
+            let mut rows: Vec<IdxSize> = (0..data.height() as IdxSize).collect();
+            for row in 0..data.height() {
+                if !rng.gen_bool(rate) {
+                    continue;
+                }
+                let copy_from = (row > 0 && !mutated[row - 1]).then(|| row - 1);
+                let mut candidates: Vec<(Option<usize>, &'static str)> = vec![];
+                if self.table.duplicate_rows.is_some() && copy_from.is_some() {
+                    candidates.push((None, "duplicate_rows"));
+                }
+                for (index, (constraint, _, cells)) in columns.iter().enumerate() {
+                    for check in _checks(constraint, cells, copy_from) {
+                        candidates.push((Some(index), check));
+                    }
+                }
+                let Some((column, check)) = candidates.choose(&mut rng).copied() else { continue };
+                mutated[row] = true;
+                let name = match column {
+                    Some(index) => {
+                        let (constraint, _, cells) = &mut columns[index];
+                        _mutate_cell(constraint, cells, row, check);
+                        constraint.name.clone()
+                    }
+                    None => {
+                        rows[row] = row as IdxSize - 1;
+                        for (_, _, cells) in columns.iter_mut() {
+                            match cells {
+                                Cells::Numbers(values) => values[row] = values[row - 1],
+                                Cells::Texts(values) => values[row] = values[row - 1].clone(),
+                            }
+                        }
+                        String::from("table")
+                    }
+                };
+                *manifest
+                    .expected
+                    .entry(name.clone())
+                    .or_default()
+                    .entry(String::from(check))
+                    .or_default() += if check == "unique" { 2 } else { 1 };
+                manifest.mutations.push(Mutation {
+                    line: row + 2,
+                    column: name,
+                    check: String::from(check),
+                });
+            }
+
+            let mut output = data.take(&IdxCa::from_vec("rows", rows))?;
+            for (_, name, cells) in columns {
+                let dtype = data.column(&name)?.dtype().clone();
+                let series = match cells {
+                    Cells::Numbers(values) => Series::new(&name, values).cast(&dtype)?,
+                    Cells::Texts(values) => Series::new(&name, values),
+                };
+                output.with_column(series)?;
+            }
+            Ok((output, manifest))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constraints::constraints::*;
+    use crate::validation::validation::ValidationSet;
+    use polars::prelude::*;
+
+    #[test]
+    fn mutate_rows() {
+        let mut df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let ids: Vec<i64> = (1..=df.height() as i64).collect();
+        df.with_column(Series::new("id", ids)).unwrap();
+        let set = ConstraintSet::new(&df, "test");
+        assert!(set.set.iter().any(|c| c.name == "id" && c.unique));
+
+        let (mutated, manifest) = set.mutate(&df, 0.05, 11).unwrap();
+        assert_eq!(mutated.shape(), df.shape());
+        assert_eq!(mutated.dtypes(), df.dtypes());
+        assert!(manifest.mutations.len() > 150);
+        assert_eq!(set.mutate(&df, 0.05, 11).unwrap().1, manifest);
+        assert!(set.mutate(&df, 5.0, 11).is_err());
+        assert!(set.mutate(&df, -1.0, 11).is_err());
+
+        let expected = |column: &str, check: &str| {
+            manifest.expected.get(column).and_then(|checks| checks.get(check)).copied()
+        };
+        let validation = ValidationSet::new(&mutated, &set);
+        assert_eq!(validation.table.duplicate_rows, expected("table", "duplicate_rows"));
+        let copied_rows = 2 * expected("table", "duplicate_rows").unwrap_or_default();
+        for v in &validation.set {
+            if v.unique.is_some() {
+                let unique = expected(&v.name, "unique").unwrap_or_default() + copied_rows;
+                assert_eq!(v.unique, Some(unique));
+                assert!(unique > copied_rows);
+            }
+            assert_eq!(v.nullable.filter(|n| *n > 0), expected(&v.name, "nullable"));
+            assert_eq!(v.min_value.filter(|n| *n > 0), expected(&v.name, "min_value"));
+            assert_eq!(v.max_value.filter(|n| *n > 0), expected(&v.name, "max_value"));
+            assert_eq!(v.max_length.filter(|n| *n > 0), expected(&v.name, "max_length"));
+        }
+    }
+
+    #[test]
+    fn mutate_aliased_columns() {
+        let df = df!("status" => &["open", "closed", "open", "closed"]).unwrap();
+        let mut set = ConstraintSet::new(&df, "test");
+        set.modify("status", "aliases", "state");
+        set.table.duplicate_rows = None;
+        let df_renamed = df!("state" => &["open", "closed", "open", "closed"]).unwrap();
+
+        let (mutated, manifest) = set.mutate(&df_renamed, 1.0, 3).unwrap();
+        assert_eq!(mutated.get_column_names(), vec!["state"]);
+        assert_eq!(manifest.mutations.len(), 4);
+        assert!(!mutated.frame_equal_missing(&df_renamed));
+        assert!(manifest.mutations.iter().all(|m| m.column == "status"));
+    }
+}
@@ -0,0 +1,278 @@
+pub mod repair {
+    use crate::constraints::constraints::*;
+    use csv::Writer;
+    use polars::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use std::fs::File;
+
+    /// A value changed by a repair, for the audit log.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Change {
+        /// Line number in the input file, counting the header as line 1.
+        pub line: usize,
+        pub column: String,
+        pub action: String,
+        pub old_value: Option<String>,
+        pub new_value: Option<String>,
+    }
+
+    fn _record<T: ToString>(changes: &mut Vec<Change>, row: usize, column: &str, action: &str, old: &Option<T>, new: &Option<T>) {
+        changes.push(Change {
+            line: row + 2,
+            column: String::from(column),
+            action: String::from(action),
+            old_value: old.as_ref().map(|v| v.to_string()),
+            new_value: new.as_ref().map(|v| v.to_string()),
+        });
+    }
+
+    /// Applies `repair` to every value, recording the values it changes.
+    fn _apply<T: Clone + PartialEq + ToString, F: Fn(&Option<T>) -> Option<T>>(
+        values: &mut [Option<T>],
+        changes: &mut Vec<Change>,
+        column: &str,
+        action: &str,
+        repair: F,
+    ) {
+        for (row, value) in values.iter_mut().enumerate() {
+            let repaired = repair(value);
+            if repaired != *value {
+                _record(changes, row, column, action, value, &repaired);
+                *value = repaired;
+            }
+        }
+    }
+
+    fn _repair_strings(constraint: &Constraint, name: &str, values: &mut [Option<String>], changes: &mut Vec<Change>) {
+        let repairs = &constraint.repairs;
+        if let Some(fill) = &repairs.fill_null {
+            _apply(values, changes, name, "fill_null", |v| v.clone().or_else(|| Some(fill.clone())));
+        }
+        if repairs.trim {
+            _apply(values, changes, name, "trim", |v| v.as_ref().map(|v| String::from(v.trim())));
+        }
+        if let (true, Some(range)) = (repairs.map_case, constraint.value_range.as_deref()) {
+            let members: Vec<&str> = range.split(", ").collect();
+            _apply(values, changes, name, "map_case", |v| {
+                let value = v.as_ref()?;
+                if members.contains(&value.as_str()) {
+                    return Some(value.clone());
+                }
+                let key = value.trim().to_lowercase();
+                let member = members.iter().find(|m| m.to_lowercase() == key);
+                Some(member.map(|m| String::from(*m)).unwrap_or_else(|| value.clone()))
+            });
+        }
+        if let (true, Some(max)) = (repairs.truncate, constraint.max_length) {
+            _apply(values, changes, name, "truncate", |v| {
+                v.as_ref().map(|v| v.chars().take(max as usize).collect())
+            });
+        }
+    }
+
+    /// Parses the `fill_null` value of a numeric column, failing when it
+    /// isn't a number of the column's kind.
+    fn _fill_value<T: std::str::FromStr>(constraint: &Constraint, kind: &str) -> PolarsResult<Option<T>> {
+        match &constraint.repairs.fill_null {
+            Some(fill) => fill.trim().parse::<T>().map(Some).map_err(|_| {
+                PolarsError::ComputeError(
+                    format!("fill_null value {} of {} is not {}", fill, constraint.name, kind).into(),
+                )
+            }),
+            None => Ok(None),
+        }
+    }
+
+    fn _repair_floats(constraint: &Constraint, name: &str, values: &mut [Option<f64>], changes: &mut Vec<Change>) -> PolarsResult<()> {
+        if let Some(fill) = _fill_value::<f64>(constraint, "a number")? {
+            _apply(values, changes, name, "fill_null", |v| v.or(Some(fill)));
+        }
+        if constraint.repairs.clamp {
+            let min = constraint.min_value.unwrap_or(f64::NEG_INFINITY);
+            let max = constraint.max_value.unwrap_or(f64::INFINITY);
+            if min <= max {
+                _apply(values, changes, name, "clamp", |v| v.map(|v| v.clamp(min, max)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Integers are repaired without going through floats, so large values
+    /// stay exact, and fractional bounds are rounded inwards. The bounds
+    /// themselves are stored as f64, so above 2^53 they are only as exact
+    /// as the nearest representable float.
+    fn _repair_integers(constraint: &Constraint, name: &str, values: &mut [Option<i128>], changes: &mut Vec<Change>) -> PolarsResult<()> {
+        if let Some(fill) = _fill_value::<i128>(constraint, "a whole number")? {
+            _apply(values, changes, name, "fill_null", |v| v.or(Some(fill)));
+        }
+        if constraint.repairs.clamp {
+            let min = constraint.min_value.map_or(i128::MIN, |min| min.ceil() as i128);
+            let max = constraint.max_value.map_or(i128::MAX, |max| max.floor() as i128);
+            if min <= max {
+                _apply(values, changes, name, "clamp", |v| v.map(|v| v.clamp(min, max)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fails when a repaired integer doesn't fit the column's data type.
+    fn _check_fits(constraint: &Constraint, dtype: &DataType, values: &[Option<i128>]) -> PolarsResult<()> {
+        let (min, max) = match dtype {
+            DataType::Int8 => (i8::MIN as i128, i8::MAX as i128),
+            DataType::Int16 => (i16::MIN as i128, i16::MAX as i128),
+            DataType::Int32 => (i32::MIN as i128, i32::MAX as i128),
+            DataType::UInt8 => (0, u8::MAX as i128),
+            DataType::UInt16 => (0, u16::MAX as i128),
+            DataType::UInt32 => (0, u32::MAX as i128),
+            DataType::UInt64 => (0, u64::MAX as i128),
+            _ => (i64::MIN as i128, i64::MAX as i128),
+        };
+        match values.iter().flatten().find(|v| !(min..=max).contains(*v)) {
+            Some(v) => Err(PolarsError::ComputeError(
+                format!("Repaired value {} of {} doesn't fit its data type", v, constraint.name).into(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    impl ConstraintSet {
+        /// Applies the repairs configured on each constraint. Returns the
+        /// repaired data and every change made, column by column. Columns
+        /// matched through an alias or ignoring case keep and log their own
+        /// name. Numeric columns keep their data type. Fails when a numeric
+        /// column has a `fill_null` value that isn't a number of its kind, or
+        /// one that doesn't fit its data type.
+        pub fn repair(&self, data: &DataFrame) -> PolarsResult<(DataFrame, Vec<Change>)> {
+            let mut repaired = data.clone();
+            let mut changes: Vec<Change> = vec![];
+            for (constraint, matched) in self.set.iter().zip(self.match_columns(data)) {
+                if constraint.repairs == Repairs::default() {
+                    continue;
+                }
+                let Some(name) = matched else { continue };
+                let col = data.column(&name)?;
+                let before = changes.len();
+                let series = if col.dtype() == &DataType::UInt64 {
+                    let mut values: Vec<Option<i128>> = col.u64()?.into_iter().map(|v| v.map(i128::from)).collect();
+                    _repair_integers(constraint, &name, &mut values, &mut changes)?;
+                    _check_fits(constraint, col.dtype(), &values)?;
+                    let values: Vec<Option<u64>> = values.into_iter().map(|v| v.map(|v| v as u64)).collect();
+                    Series::new(&name, values)
+                } else if col.dtype().is_integer() {
+                    let mut values: Vec<Option<i128>> =
+                        col.cast(&DataType::Int64)?.i64()?.into_iter().map(|v| v.map(i128::from)).collect();
+                    _repair_integers(constraint, &name, &mut values, &mut changes)?;
+                    _check_fits(constraint, col.dtype(), &values)?;
+                    let values: Vec<Option<i64>> = values.into_iter().map(|v| v.map(|v| v as i64)).collect();
+                    Series::new(&name, values).cast(col.dtype())?
+                } else if col.dtype().is_float() {
+                    let mut values: Vec<Option<f64>> = col.cast(&DataType::Float64)?.f64()?.into_iter().collect();
+                    _repair_floats(constraint, &name, &mut values, &mut changes)?;
+                    Series::new(&name, values).cast(col.dtype())?
+                } else if col.dtype() == &DataType::Utf8 {
+                    let mut values: Vec<Option<String>> =
+                        col.utf8()?.into_iter().map(|v| v.map(String::from)).collect();
+                    _repair_strings(constraint, &name, &mut values, &mut changes);
+                    Series::new(&name, values)
+                } else {
+                    continue;
+                };
+                if changes.len() > before {
+                    repaired.with_column(series)?;
+                }
+            }
+            Ok((repaired, changes))
+        }
+    }
+
+    /// Writes the audit log of a repair as csv.
+    pub fn save_audit_log(changes: &[Change], filepath: &str) -> Result<(), String> {
+        let file = File::create(filepath).map_err(|e| format!("Failed to create file: {}", e))?;
+        let mut writer = Writer::from_writer(file);
+        for change in changes {
+            writer.serialize(change).map_err(|e| format!("Failed to serialize data: {}", e))?;
+        }
+        writer.flush().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::repair::*;
+    use crate::constraints::constraints::*;
+    use polars::prelude::*;
+
+    #[test]
+    fn repair_values() {
+        let df_good = df!(
+            "status" => &["open", "closed"],
+            "score" => &[1, 10]
+        )
+        .unwrap();
+        let df_bad = df!(
+            "status" => &[Some(" Open"), None, Some("closed!!")],
+            "score" => &[Some(0), Some(5), Some(12)]
+        )
+        .unwrap();
+
+        let mut set = ConstraintSet::new(&df_good, "test");
+        set.modify("status", "repairs.fill_null", "open");
+        set.modify("status", "repairs.map_case", "true");
+        set.modify("status", "repairs.truncate", "true");
+        set.modify("score", "repairs.clamp", "true");
+        let (repaired, changes) = set.repair(&df_bad).unwrap();
+
+        let status: Vec<Option<&str>> = repaired.column("status").unwrap().utf8().unwrap().into_iter().collect();
+        assert_eq!(status, vec![Some("open"), Some("open"), Some("closed")]);
+        let score: Vec<Option<i32>> = repaired.column("score").unwrap().i32().unwrap().into_iter().collect();
+        assert_eq!(score, vec![Some(1), Some(5), Some(10)]);
+
+        assert_eq!(changes.len(), 5);
+        assert_eq!(
+            changes[0],
+            Change {
+                line: 3,
+                column: String::from("status"),
+                action: String::from("fill_null"),
+                old_value: None,
+                new_value: Some(String::from("open")),
+            }
+        );
+        assert_eq!(changes[2].action, "truncate");
+        assert_eq!(changes[3].old_value.as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn repair_aliased_columns() {
+        let mut set = ConstraintSet::new(&df!("status" => &["open", "closed"]).unwrap(), "test");
+        set.modify("status", "aliases", "state");
+        set.modify("status", "repairs.trim", "true");
+        let (repaired, changes) = set.repair(&df!("state" => &[" open", "closed"]).unwrap()).unwrap();
+        let state: Vec<Option<&str>> = repaired.column("state").unwrap().utf8().unwrap().into_iter().collect();
+        assert_eq!(state, vec![Some("open"), Some("closed")]);
+        assert_eq!((changes.len(), changes[0].column.as_str()), (1, "state"));
+    }
+
+    #[test]
+    fn repair_integers_exactly() {
+        let big = 9_007_199_254_740_993_i64;
+        let df_bad = df!("id" => &[Some(big), Some(big + 4), None, Some(2)]).unwrap();
+        let mut set = ConstraintSet::new(&df!("id" => &[1_i64, 2]).unwrap(), "test");
+        set.modify("id", "min_value", "2.5");
+        set.modify("id", "max_value", "9007199254740994");
+        set.modify("id", "repairs.clamp", "true");
+        set.modify("id", "repairs.fill_null", "7");
+        let (repaired, changes) = set.repair(&df_bad).unwrap();
+        let id: Vec<Option<i64>> = repaired.column("id").unwrap().i64().unwrap().into_iter().collect();
+        assert_eq!(id, vec![Some(big), Some(big + 1), Some(7), Some(3)]);
+        assert_eq!(changes[1].old_value.as_deref(), Some("9007199254740997"));
+
+        set.modify("id", "repairs.fill_null", "unknown");
+        assert!(set.repair(&df_bad).is_err());
+        set.modify("id", "repairs.fill_null", "7.5");
+        assert!(set.repair(&df_bad).is_err());
+        set.modify("id", "repairs.clamp", "false");
+        set.modify("id", "repairs.fill_null", "-1");
+        assert!(set.repair(&df!("id" => &[Some(1_u32), None]).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,371 @@
+pub mod schema {
+    use crate::constraints::constraints::*;
+    use serde_json::{json, Map, Value};
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::Path;
+
+    /// Maps a dvrs data type to the JSON Schema type and optional format.
+    fn _json_schema_type(data_type: &ColumnType) -> (&'static str, Option<&'static str>) {
+        match data_type {
+            ColumnType::Bool => ("boolean", None),
+            ColumnType::Date => ("string", Some("date")),
+            ColumnType::Datetime => ("string", Some("date-time")),
+            ColumnType::Time => ("string", Some("time")),
+            t if t.is_integer() => ("integer", None),
+            t if t.is_numeric() => ("number", None),
+            _ => ("string", None),
+        }
+    }
+
+    /// Maps a dvrs data type to the Frictionless Table Schema field type.
+    fn _table_schema_type(data_type: &ColumnType) -> &'static str {
+        match data_type {
+            ColumnType::Utf8 | ColumnType::Categorical => "string",
+            ColumnType::Bool => "boolean",
+            ColumnType::Date => "date",
+            ColumnType::Datetime => "datetime",
+            ColumnType::Time => "time",
+            ColumnType::Duration => "duration",
+            t if t.is_integer() => "integer",
+            t if t.is_numeric() => "number",
+            _ => "any",
+        }
+    }
+
+    /// Maps a JSON Schema or Table Schema type back to a dvrs data type.
+    fn _data_type_from_schema(schema_type: &str, format: Option<&str>) -> ColumnType {
+        match (schema_type, format) {
+            ("string", Some("date")) | ("date", _) => ColumnType::Date,
+            ("string", Some("date-time")) | ("datetime", _) => ColumnType::Datetime,
+            ("string", Some("time")) | ("time", _) => ColumnType::Time,
+            ("duration", _) => ColumnType::Duration,
+            ("string", _) => ColumnType::Utf8,
+            ("integer", _) => ColumnType::Integer,
+            ("number", _) => ColumnType::Numeric,
+            ("boolean", _) => ColumnType::Bool,
+            _ => ColumnType::Any,
+        }
+    }
+
+    /// Non-null entries of a `value_range`, in the order they were saved.
+    fn _enum_values(constraint: &Constraint) -> Option<Vec<Value>> {
+        constraint.value_range.as_ref().map(|range| {
+            range
+                .split(", ")
+                .filter(|v| *v != "null")
+                .map(Value::from)
+                .collect()
+        })
+    }
+
+    fn _value_range_from_enum(values: Option<&Value>) -> Option<String> {
+        values.and_then(Value::as_array).map(|values| {
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<&str>>()
+                .join(", ")
+        })
+    }
+
+    fn _as_u32(value: Option<&Value>) -> Option<u32> {
+        value.and_then(Value::as_u64).and_then(|v| u32::try_from(v).ok())
+    }
+
+    fn _write_json(value: &Value, filepath: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(value).map_err(|err| err.to_string())?;
+        let mut file = File::create(Path::new(filepath)).map_err(|err| err.to_string())?;
+        file.write_all(json.as_bytes()).map_err(|err| err.to_string())?;
+        Ok(())
+    }
+
+    impl ConstraintSet {
+        /// Exports the set as a JSON Schema describing an array of rows.
+        /// `unique` has no JSON Schema keyword for object properties, so it is
+        /// written as a `unique` annotation that validators ignore.
+        pub fn to_json_schema(&self) -> Value {
+            let mut properties = Map::new();
+            let mut required = vec![];
+            for constraint in &self.set {
+                let (schema_type, format) = _json_schema_type(&constraint.data_type);
+                let mut property = Map::new();
+                if constraint.nullable {
+                    property.insert(String::from("type"), json!([schema_type, "null"]));
+                } else {
+                    property.insert(String::from("type"), json!(schema_type));
+                }
+                let format = match constraint.semantic_type {
+                    Some(SemanticType::Email) => Some("email"),
+                    Some(SemanticType::Url) => Some("uri"),
+                    Some(SemanticType::Uuid) => Some("uuid"),
+                    Some(SemanticType::Ipv4) => Some("ipv4"),
+                    Some(SemanticType::Ipv6) => Some("ipv6"),
+                    _ => format,
+                };
+                if let Some(format) = format {
+                    property.insert(String::from("format"), json!(format));
+                }
+                if let Some(description) = &constraint.description {
+                    property.insert(String::from("description"), json!(description));
+                }
+                if constraint.unique {
+                    property.insert(String::from("unique"), json!(true));
+                }
+                if let Some(min_length) = constraint.min_length {
+                    property.insert(String::from("minLength"), json!(min_length));
+                }
+                if let Some(max_length) = constraint.max_length {
+                    property.insert(String::from("maxLength"), json!(max_length));
+                }
+                if let Some(min_value) = constraint.min_value {
+                    property.insert(String::from("minimum"), json!(min_value));
+                }
+                if let Some(max_value) = constraint.max_value {
+                    property.insert(String::from("maximum"), json!(max_value));
+                }
+                if let Some(mut values) = _enum_values(constraint) {
+                    if constraint.nullable {
+                        values.push(Value::Null);
+                    }
+                    property.insert(String::from("enum"), Value::Array(values));
+                }
+                properties.insert(constraint.name.clone(), Value::Object(property));
+                required.push(constraint.name.clone());
+            }
+            let mut schema = json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": self.name,
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                },
+            });
+            if let Some(description) = &self.metadata.description {
+                schema["description"] = json!(description);
+            }
+            schema
+        }
+
+        /// Builds a set from a JSON Schema produced by `to_json_schema` or any
+        /// schema with the same row-array shape.
+        pub fn from_json_schema(schema: &Value) -> Result<ConstraintSet, String> {
+            let properties = schema
+                .pointer("/items/properties")
+                .and_then(Value::as_object)
+                .ok_or_else(|| String::from("JSON Schema must describe an array of row objects"))?;
+
+            let mut set = vec![];
+            for (name, property) in properties {
+                let types: Vec<&str> = match property.get("type") {
+                    Some(Value::String(t)) => vec![t.as_str()],
+                    Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+                    _ => vec![],
+                };
+                let schema_type = types.iter().find(|t| **t != "null").copied().unwrap_or("null");
+                let format = property.get("format").and_then(Value::as_str);
+                set.push(Constraint {
+                    description: property.get("description").and_then(Value::as_str).map(String::from),
+                    data_type: _data_type_from_schema(schema_type, format),
+                    nullable: types.contains(&"null"),
+                    unique: property.get("unique").and_then(Value::as_bool).unwrap_or_default(),
+                    min_length: _as_u32(property.get("minLength")),
+                    max_length: _as_u32(property.get("maxLength")),
+                    min_value: property.get("minimum").and_then(Value::as_f64),
+                    max_value: property.get("maximum").and_then(Value::as_f64),
+                    value_range: _value_range_from_enum(property.get("enum")),
+                    semantic_type: match format {
+                        Some("email") => Some(SemanticType::Email),
+                        Some("uri") => Some(SemanticType::Url),
+                        Some("uuid") => Some(SemanticType::Uuid),
+                        Some("ipv4") => Some(SemanticType::Ipv4),
+                        Some("ipv6") => Some(SemanticType::Ipv6),
+                        _ => None,
+                    },
+                    ..Constraint::named(name)
+                });
+            }
+            Ok(ConstraintSet {
+                format_version: FORMAT_VERSION,
+                name: schema
+                    .get("title")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                metadata: Metadata {
+                    description: schema.get("description").and_then(Value::as_str).map(String::from),
+                    ..Default::default()
+                },
+                name_matching: NameMatching::default(),
+                set,
+                table: TableConstraint::default(),
+            })
+        }
+
+        /// Exports the set as a Frictionless Data Table Schema.
+        pub fn to_table_schema(&self) -> Value {
+            let fields: Vec<Value> = self
+                .set
+                .iter()
+                .map(|constraint| {
+                    let mut constraints = Map::new();
+                    constraints.insert(String::from("required"), json!(!constraint.nullable));
+                    constraints.insert(String::from("unique"), json!(constraint.unique));
+                    if let Some(min_length) = constraint.min_length {
+                        constraints.insert(String::from("minLength"), json!(min_length));
+                    }
+                    if let Some(max_length) = constraint.max_length {
+                        constraints.insert(String::from("maxLength"), json!(max_length));
+                    }
+                    if let Some(min_value) = constraint.min_value {
+                        constraints.insert(String::from("minimum"), json!(min_value));
+                    }
+                    if let Some(max_value) = constraint.max_value {
+                        constraints.insert(String::from("maximum"), json!(max_value));
+                    }
+                    if let Some(values) = _enum_values(constraint) {
+                        constraints.insert(String::from("enum"), Value::Array(values));
+                    }
+                    let mut field = json!({
+                        "name": constraint.name,
+                        "type": _table_schema_type(&constraint.data_type),
+                        "constraints": constraints,
+                    });
+                    if let Some(description) = &constraint.description {
+                        field["description"] = json!(description);
+                    }
+                    field
+                })
+                .collect();
+            let mut schema = json!({ "name": self.name, "fields": fields });
+            if let Some(description) = &self.metadata.description {
+                schema["description"] = json!(description);
+            }
+            schema
+        }
+
+        /// Builds a set from a Frictionless Data Table Schema.
+        pub fn from_table_schema(schema: &Value) -> Result<ConstraintSet, String> {
+            let fields = schema
+                .get("fields")
+                .and_then(Value::as_array)
+                .ok_or_else(|| String::from("Table Schema must have a fields array"))?;
+
+            let mut set = vec![];
+            for field in fields {
+                let name = field
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| String::from("Table Schema field is missing a name"))?;
+                let schema_type = field.get("type").and_then(Value::as_str).unwrap_or("string");
+                let empty = Map::new();
+                let constraints = field
+                    .get("constraints")
+                    .and_then(Value::as_object)
+                    .unwrap_or(&empty);
+                set.push(Constraint {
+                    description: field.get("description").and_then(Value::as_str).map(String::from),
+                    data_type: _data_type_from_schema(schema_type, None),
+                    nullable: !constraints
+                        .get("required")
+                        .and_then(Value::as_bool)
+                        .unwrap_or_default(),
+                    unique: constraints.get("unique").and_then(Value::as_bool).unwrap_or_default(),
+                    min_length: _as_u32(constraints.get("minLength")),
+                    max_length: _as_u32(constraints.get("maxLength")),
+                    min_value: constraints.get("minimum").and_then(Value::as_f64),
+                    max_value: constraints.get("maximum").and_then(Value::as_f64),
+                    value_range: _value_range_from_enum(constraints.get("enum")),
+                    ..Constraint::named(name)
+                });
+            }
+            Ok(ConstraintSet {
+                format_version: FORMAT_VERSION,
+                name: schema
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                metadata: Metadata {
+                    description: schema.get("description").and_then(Value::as_str).map(String::from),
+                    ..Default::default()
+                },
+                name_matching: NameMatching::default(),
+                set,
+                table: TableConstraint::default(),
+            })
+        }
+
+        /// Reads a JSON Schema or, when it has a `fields` array, a Table
+        /// Schema. A schema without a title or name is named after the file.
+        pub fn read_schema(filepath: &str) -> Result<ConstraintSet, String> {
+            let contents = std::fs::read_to_string(filepath).map_err(|err| err.to_string())?;
+            let schema: Value = serde_json::from_str(&contents).map_err(|err| err.to_string())?;
+            let mut set = if schema.get("fields").is_some() {
+                Self::from_table_schema(&schema)?
+            } else {
+                Self::from_json_schema(&schema)?
+            };
+            if set.name.is_empty() {
+                if let Some(stem) = Path::new(filepath).file_stem() {
+                    set.name = stem.to_string_lossy().to_string();
+                }
+            }
+            Ok(set)
+        }
+
+        pub fn save_json_schema(&self, filepath: &str) -> Result<(), String> {
+            _write_json(&self.to_json_schema(), filepath)
+        }
+
+        pub fn save_table_schema(&self, filepath: &str) -> Result<(), String> {
+            _write_json(&self.to_table_schema(), filepath)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constraints::constraints::*;
+    use polars::prelude::*;
+
+    #[test]
+    fn schema_round_trip() {
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let set = ConstraintSet::new(&df, "test");
+
+        let json_schema = set.to_json_schema();
+        assert_eq!(json_schema["items"]["properties"]["age"]["type"][0], "number");
+        assert_eq!(json_schema["items"]["properties"]["age"]["minimum"], 0.08);
+        assert_eq!(json_schema["items"]["properties"]["Residence_type"]["maxLength"], 5);
+        let imported = ConstraintSet::from_json_schema(&json_schema).unwrap();
+        let gender = imported.set.iter().find(|c| c.name == "gender").unwrap();
+        assert_eq!(gender.data_type, ColumnType::Utf8);
+        assert!(!gender.nullable);
+        assert_eq!(gender.min_length, Some(4));
+
+        let table_schema = set.to_table_schema();
+        assert_eq!(table_schema["fields"][1]["name"], "age");
+        assert_eq!(table_schema["fields"][1]["constraints"]["required"], false);
+        let imported = ConstraintSet::from_table_schema(&table_schema).unwrap();
+        assert_eq!(imported.set.len(), set.set.len());
+        let bmi = imported.set.iter().find(|c| c.name == "bmi").unwrap();
+        assert!(bmi.nullable);
+        assert_eq!(bmi.max_value, set.set[8].max_value);
+
+        let path = std::env::temp_dir().join("dvrs_orders.schema.json");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, r#"{"fields": [{"name": "id", "type": "integer"}]}"#).unwrap();
+        let imported = ConstraintSet::read_schema(path).unwrap();
+        assert_eq!(imported.name, "dvrs_orders.schema");
+        assert_eq!(imported.set[0].data_type, ColumnType::Integer);
+        assert_eq!(imported.set[0].allowed_chars, None);
+        set.save_json_schema(path).unwrap();
+        assert_eq!(ConstraintSet::read_schema(path).unwrap().set.len(), set.set.len());
+    }
+}
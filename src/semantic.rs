@@ -0,0 +1,221 @@
+pub mod semantic {
+    use regex::Regex;
+    use serde::{Deserialize, Serialize};
+    use std::fmt;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use std::str::FromStr;
+    use std::sync::OnceLock;
+
+    /// Well-known string formats that `min_length`/`max_length` can't capture.
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[serde(rename_all = "snake_case")]
+    pub enum SemanticType {
+        Email,
+        Url,
+        Uuid,
+        Ipv4,
+        Ipv6,
+        /// E.164 phone number, e.g. `+14155552671`.
+        Phone,
+        /// ISO-3166 alpha-2 country code.
+        CountryCode,
+        /// ISO-4217 currency code.
+        CurrencyCode,
+        Iban,
+    }
+
+    /// Order in which semantic types are tried during detection, most
+    /// specific first.
+    pub const SEMANTIC_TYPES: [SemanticType; 9] = [
+        SemanticType::Uuid,
+        SemanticType::Ipv4,
+        SemanticType::Ipv6,
+        SemanticType::Email,
+        SemanticType::Url,
+        SemanticType::Phone,
+        SemanticType::Iban,
+        SemanticType::CurrencyCode,
+        SemanticType::CountryCode,
+    ];
+
+    const COUNTRY_CODES: &str = "AD AE AF AG AI AL AM AO AQ AR AS AT AU AW AX AZ BA BB BD BE BF BG BH BI BJ \
+        BL BM BN BO BQ BR BS BT BV BW BY BZ CA CC CD CF CG CH CI CK CL CM CN CO CR CU CV CW CX CY CZ DE DJ DK \
+        DM DO DZ EC EE EG EH ER ES ET FI FJ FK FM FO FR GA GB GD GE GF GG GH GI GL GM GN GP GQ GR GS GT GU GW \
+        GY HK HM HN HR HT HU ID IE IL IM IN IO IQ IR IS IT JE JM JO JP KE KG KH KI KM KN KP KR KW KY KZ LA LB \
+        LC LI LK LR LS LT LU LV LY MA MC MD ME MF MG MH MK ML MM MN MO MP MQ MR MS MT MU MV MW MX MY MZ NA NC \
+        NE NF NG NI NL NO NP NR NU NZ OM PA PE PF PG PH PK PL PM PN PR PS PT PW PY QA RE RO RS RU RW SA SB SC \
+        SD SE SG SH SI SJ SK SL SM SN SO SR SS ST SV SX SY SZ TC TD TF TG TH TJ TK TL TM TN TO TR TT TV TW TZ \
+        UA UG UM US UY UZ VA VC VE VG VI VN VU WF WS YE YT ZA ZM ZW";
+
+    const CURRENCY_CODES: &str = "AED AFN ALL AMD ANG AOA ARS AUD AWG AZN BAM BBD BDT BGN BHD BIF BMD BND BOB \
+        BRL BSD BTN BWP BYN BZD CAD CDF CHF CLP CNY COP CRC CUP CVE CZK DJF DKK DOP DZD EGP ERN ETB EUR FJD \
+        FKP GBP GEL GHS GIP GMD GNF GTQ GYD HKD HNL HTG HUF IDR ILS INR IQD IRR ISK JMD JOD JPY KES KGS KHR \
+        KMF KPW KRW KWD KYD KZT LAK LBP LKR LRD LSL LYD MAD MDL MGA MKD MMK MNT MOP MRU MUR MVR MWK MXN MYR \
+        MZN NAD NGN NIO NOK NPR NZD OMR PAB PEN PGK PHP PKR PLN PYG QAR RON RSD RUB RWF SAR SBD SCR SDG SEK \
+        SGD SHP SLE SLL SOS SRD SSP STN SVC SYP SZL THB TJS TMT TND TOP TRY TTD TWD TZS UAH UGX USD UYU UZS \
+        VED VES VND VUV WST XAF XAG XAU XCD XDR XOF XPD XPF XPT XSU XUA YER ZAR ZMW ZWL";
+
+    fn _regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+        cell.get_or_init(|| Regex::new(pattern).unwrap())
+    }
+
+    /// Remainder modulo 97 of a text of digits and uppercase letters, with
+    /// letters read as the numbers 10 to 35.
+    fn _mod97(chars: impl Iterator<Item = char>) -> u32 {
+        let mut remainder: u32 = 0;
+        for c in chars {
+            let digits = c.to_digit(36).unwrap_or_default();
+            remainder = if digits >= 10 {
+                (remainder * 100 + digits) % 97
+            } else {
+                (remainder * 10 + digits) % 97
+            };
+        }
+        remainder
+    }
+
+    /// IBAN checksum: move the first four characters to the end, turn
+    /// letters into numbers and check the remainder modulo 97 is 1.
+    fn _is_iban(value: &str) -> bool {
+        let iban: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+        if iban.len() < 15
+            || iban.len() > 34
+            || !iban.chars().all(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+            || !COUNTRY_CODES.split_whitespace().any(|c| iban.starts_with(c))
+        {
+            return false;
+        }
+        _mod97(iban[4..].chars().chain(iban[..4].chars())) == 1
+    }
+
+    impl SemanticType {
+        /// Whether a raw string value has this format.
+        pub fn matches(&self, value: &str) -> bool {
+            static EMAIL: OnceLock<Regex> = OnceLock::new();
+            static URL: OnceLock<Regex> = OnceLock::new();
+            static UUID: OnceLock<Regex> = OnceLock::new();
+            static PHONE: OnceLock<Regex> = OnceLock::new();
+
+            let value = value.trim();
+            match self {
+                SemanticType::Email => _regex(&EMAIL, r"^[^@\s]+@[^@\s]+\.[A-Za-z]{2,}$").is_match(value),
+                SemanticType::Url => _regex(&URL, r"^[A-Za-z][A-Za-z0-9+.-]*://[^\s/?#]+[^\s]*$").is_match(value),
+                SemanticType::Uuid => _regex(
+                    &UUID,
+                    r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
+                )
+                .is_match(value),
+                SemanticType::Ipv4 => Ipv4Addr::from_str(value).is_ok(),
+                SemanticType::Ipv6 => Ipv6Addr::from_str(value).is_ok(),
+                SemanticType::Phone => _regex(&PHONE, r"^\+[1-9][0-9]{1,14}$").is_match(value),
+                SemanticType::CountryCode => {
+                    value.len() == 2 && COUNTRY_CODES.split_whitespace().any(|c| c == value)
+                }
+                SemanticType::CurrencyCode => {
+                    value.len() == 3 && CURRENCY_CODES.split_whitespace().any(|c| c == value)
+                }
+                SemanticType::Iban => _is_iban(value),
+            }
+        }
+
+        /// A valid value of this type derived from `n`, used to generate
+        /// synthetic data. Different numbers give different values for all
+        /// but the code types.
+        pub fn example(&self, n: u64) -> String {
+            match self {
+                SemanticType::Email => format!("user{}@example.com", n),
+                SemanticType::Url => format!("https://example.com/{}", n),
+                SemanticType::Uuid => {
+                    let h = n.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+                    format!(
+                        "{:08x}-{:04x}-4{:03x}-a{:03x}-{:012x}",
+                        h >> 32,
+                        (h >> 16) & 0xffff,
+                        h & 0xfff,
+                        (n >> 48) & 0xfff,
+                        n & 0xffff_ffff_ffff
+                    )
+                }
+                SemanticType::Ipv4 => format!("10.{}.{}.{}", (n >> 16) & 255, (n >> 8) & 255, n & 255),
+                SemanticType::Ipv6 => format!("2001:db8::{:x}:{:x}", (n >> 16) & 0xffff, n & 0xffff),
+                SemanticType::Phone => format!("+1{}", 2_000_000_000 + n % 8_000_000_000),
+                SemanticType::CountryCode => {
+                    let codes: Vec<&str> = COUNTRY_CODES.split_whitespace().collect();
+                    String::from(codes[(n % codes.len() as u64) as usize])
+                }
+                SemanticType::CurrencyCode => {
+                    let codes: Vec<&str> = CURRENCY_CODES.split_whitespace().collect();
+                    String::from(codes[(n % codes.len() as u64) as usize])
+                }
+                SemanticType::Iban => {
+                    let bban = format!("WEST{:014}", n % 100_000_000_000_000);
+                    let check = 98 - _mod97(bban.chars().chain("GB00".chars()));
+                    format!("GB{:02}{}", check, bban)
+                }
+            }
+        }
+
+        /// Detects the semantic type matched by at least `threshold` of the values.
+        pub fn detect(values: &[&str], threshold: f64) -> Option<SemanticType> {
+            if values.is_empty() {
+                return None;
+            }
+            SEMANTIC_TYPES.iter().copied().find(|t| {
+                let matched = values.iter().filter(|v| t.matches(v)).count();
+                matched as f64 / values.len() as f64 >= threshold
+            })
+        }
+    }
+
+    impl FromStr for SemanticType {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<SemanticType, String> {
+            serde_json::from_value(serde_json::Value::from(s.to_lowercase()))
+                .map_err(|_| format!("Unknown semantic type: {}", s))
+        }
+    }
+
+    impl fmt::Display for SemanticType {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = serde_json::to_value(self).unwrap_or_default();
+            write!(f, "{}", name.as_str().unwrap_or_default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::semantic::SemanticType;
+
+    #[test]
+    fn semantic_validators() {
+        assert!(SemanticType::Email.matches("jane.doe@example.com"));
+        assert!(!SemanticType::Email.matches("jane.doe@example"));
+        assert!(SemanticType::Url.matches("https://example.com/path?q=1"));
+        assert!(!SemanticType::Url.matches("example.com"));
+        assert!(SemanticType::Uuid.matches("123e4567-e89b-12d3-a456-426614174000"));
+        assert!(SemanticType::Ipv4.matches("192.168.0.1"));
+        assert!(!SemanticType::Ipv4.matches("256.1.1.1"));
+        assert!(SemanticType::Ipv6.matches("2001:db8::1"));
+        assert!(SemanticType::Phone.matches("+14155552671"));
+        assert!(!SemanticType::Phone.matches("4155552671"));
+        assert!(SemanticType::CountryCode.matches("AR"));
+        assert!(!SemanticType::CountryCode.matches("XX"));
+        assert!(SemanticType::CurrencyCode.matches("EUR"));
+        assert!(SemanticType::Iban.matches("GB82 WEST 1234 5698 7654 32"));
+        assert!(!SemanticType::Iban.matches("GB82WEST12345698765433"));
+
+        for semantic_type in super::semantic::SEMANTIC_TYPES {
+            for n in [0, 42, u64::MAX] {
+                assert!(semantic_type.matches(&semantic_type.example(n)), "{:?} {}", semantic_type, n);
+            }
+        }
+
+        let values = ["a@example.com", "b@example.org", "c@example.net"];
+        assert_eq!(SemanticType::detect(&values, 0.9), Some(SemanticType::Email));
+        assert_eq!(SemanticType::detect(&["Male", "Female"], 0.9), None);
+        assert_eq!("country_code".parse::<SemanticType>(), Ok(SemanticType::CountryCode));
+        assert_eq!(SemanticType::CurrencyCode.to_string(), "currency_code");
+    }
+}
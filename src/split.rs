@@ -0,0 +1,157 @@
+pub mod split {
+    use crate::constraints::constraints::*;
+    use crate::validation::validation::{row_failures, Finding};
+    use polars::prelude::*;
+    use std::fs::File;
+    use std::path::Path;
+
+    /// Name of the column listing the failed checks of rejected rows.
+    pub const FAILED_CHECKS_COLUMN: &str = "_failed_checks";
+    /// Name of the column with the line number of rejected rows.
+    pub const LINE_COLUMN: &str = "_line";
+
+    impl ConstraintSet {
+        /// Failed checks of every row, in row order. Fails when columns
+        /// matched by alias or ignoring case can't be renamed.
+        pub fn row_findings(&self, data: &DataFrame) -> PolarsResult<Vec<Vec<Finding>>> {
+            let aligned = self.align_columns(data)?;
+            let mut findings: Vec<Vec<Finding>> = vec![vec![]; data.height()];
+            if let Some(check) = &self.table.duplicate_rows {
+                for (row, _) in check.find(&aligned).unwrap_or_default() {
+                    findings[row].push(Finding {
+                        column: String::from("table"),
+                        check: String::from("duplicate_rows"),
+                        failures: None,
+                        severity: self.table.severity_of("duplicate_rows"),
+                    });
+                }
+            }
+            for constraint in &self.set {
+                for (check, mask) in row_failures(&aligned, constraint) {
+                    for (row, failed) in mask.into_iter().enumerate() {
+                        if failed && row < findings.len() {
+                            findings[row].push(Finding {
+                                column: String::from(&constraint.name),
+                                check: String::from(check),
+                                failures: None,
+                                severity: constraint.severity_of(check),
+                            });
+                        }
+                    }
+                }
+            }
+            Ok(findings)
+        }
+
+        /// Splits the data into clean rows and rows failing a check with
+        /// error severity. Rejected rows get their line number and the list
+        /// of checks they failed, warnings included, as extra columns.
+        pub fn split(&self, data: &DataFrame) -> PolarsResult<(DataFrame, DataFrame)> {
+            let findings = self.row_findings(data)?;
+            let rejected: Vec<bool> = findings
+                .iter()
+                .map(|row| row.iter().any(|f| f.severity == Severity::Error))
+                .collect();
+            let mask = BooleanChunked::from_slice("rejected", &rejected);
+
+            let clean = data.filter(&!&mask)?;
+            let mut rejected_rows = data.filter(&mask)?;
+            let rejected_findings: Vec<&Vec<Finding>> = findings
+                .iter()
+                .zip(&rejected)
+                .filter_map(|(row, rejected)| rejected.then_some(row))
+                .collect();
+            let lines: Vec<u32> = rejected
+                .iter()
+                .enumerate()
+                .filter_map(|(row, rejected)| rejected.then_some(row as u32 + 2))
+                .collect();
+            let checks: Vec<String> = rejected_findings
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|f| format!("{}.{}", f.column, f.check))
+                        .collect::<Vec<String>>()
+                        .join("; ")
+                })
+                .collect();
+            rejected_rows.with_column(Series::new(LINE_COLUMN, lines))?;
+            rejected_rows.with_column(Series::new(FAILED_CHECKS_COLUMN, checks))?;
+            Ok((clean, rejected_rows))
+        }
+    }
+
+    /// Writes a DataFrame as Parquet when the path ends in `.parquet`,
+    /// otherwise as CSV.
+    pub fn write_frame(data: &mut DataFrame, filepath: &str) -> Result<(), String> {
+        let file = File::create(filepath).map_err(|e| format!("Failed to create file: {}", e))?;
+        let is_parquet = Path::new(filepath)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("parquet"));
+        if is_parquet {
+            ParquetWriter::new(file).finish(data).map(|_| ()).map_err(|e| e.to_string())
+        } else {
+            CsvWriter::new(file).finish(data).map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split::*;
+    use crate::constraints::constraints::*;
+    use crate::validation::validation::ValidationSet;
+    use polars::prelude::*;
+
+    #[test]
+    fn split_rows() {
+        let df_good = df!(
+            "id" => &[1, 2, 3, 4],
+            "status" => &["open", "closed", "open", "closed"]
+        )
+        .unwrap();
+        let df_bad = df!(
+            "id" => &[1, 2, -3, 4, 4],
+            "status" => &["open", "pending", "open", "closed", "closed"]
+        )
+        .unwrap();
+
+        let mut set = ConstraintSet::new(&df_good, "test");
+        set.modify("status", "severity", "warning");
+        let (clean, rejected) = set.split(&df_bad).unwrap();
+        assert_eq!(clean.height(), 2);
+        assert_eq!(rejected.height(), 3);
+
+        let lines: Vec<Option<u32>> = rejected.column(LINE_COLUMN).unwrap().u32().unwrap().into_iter().collect();
+        assert_eq!(lines, vec![Some(4), Some(5), Some(6)]);
+        let checks = rejected.column(FAILED_CHECKS_COLUMN).unwrap();
+        assert_eq!(
+            checks.utf8().unwrap().get(0),
+            Some("id.min_value; id.positive; id.non_negative; id.monotonic")
+        );
+        assert_eq!(
+            checks.utf8().unwrap().get(2),
+            Some("table.duplicate_rows; id.unique; id.monotonic")
+        );
+        assert!(set.row_findings(&df_bad).unwrap()[1].iter().all(|f| f.severity == Severity::Warning));
+    }
+
+    #[test]
+    fn row_findings_match_validation() {
+        let df_good: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv").unwrap().finish().unwrap();
+        let df_bad: DataFrame = CsvReader::from_path("test_data/brain_stroke_bad.csv").unwrap().finish().unwrap();
+        let set = ConstraintSet::new(&df_good, "test");
+
+        let findings = set.row_findings(&df_bad).unwrap();
+        let validation = ValidationSet::new(&df_bad, &set);
+        for finding in validation.findings.iter().filter(|f| f.failures.is_some()) {
+            let rows = findings
+                .iter()
+                .filter(|row| row.iter().any(|f| f.column == finding.column && f.check == finding.check))
+                .count();
+            assert_eq!(Some(rows as u32), finding.failures, "{}.{}", finding.column, finding.check);
+        }
+        let work_type = findings.iter().flatten().filter(|f| f.column == "work_type").count();
+        assert_eq!(work_type, 0);
+    }
+}
@@ -0,0 +1,202 @@
+pub mod sql {
+    use crate::constraints::constraints::*;
+    use std::fmt;
+    use std::str::FromStr;
+
+    /// SQL dialects supported by `ConstraintSet::to_sql`.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum SqlDialect {
+        Postgres,
+        Sqlite,
+        Mysql,
+    }
+
+    impl FromStr for SqlDialect {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<SqlDialect, String> {
+            match s.to_lowercase().as_str() {
+                "postgres" | "postgresql" => Ok(SqlDialect::Postgres),
+                "sqlite" => Ok(SqlDialect::Sqlite),
+                "mysql" => Ok(SqlDialect::Mysql),
+                _ => Err(format!("Unknown SQL dialect: {}", s)),
+            }
+        }
+    }
+
+    impl fmt::Display for SqlDialect {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                SqlDialect::Postgres => write!(f, "postgres"),
+                SqlDialect::Sqlite => write!(f, "sqlite"),
+                SqlDialect::Mysql => write!(f, "mysql"),
+            }
+        }
+    }
+
+    impl SqlDialect {
+        fn quote(&self, identifier: &str) -> String {
+            match self {
+                SqlDialect::Mysql => format!("`{}`", identifier.replace('`', "``")),
+                _ => format!("\"{}\"", identifier.replace('"', "\"\"")),
+            }
+        }
+
+        fn length_fn(&self) -> &'static str {
+            match self {
+                SqlDialect::Mysql => "CHAR_LENGTH",
+                _ => "length",
+            }
+        }
+
+        /// Column type for a dvrs data type. Strings get a bounded VARCHAR
+        /// where the dialect benefits from it.
+        fn column_type(&self, constraint: &Constraint) -> String {
+            let varchar = || format!("VARCHAR({})", constraint.max_length.unwrap_or(255));
+            let sql_type = match self {
+                SqlDialect::Sqlite => match &constraint.data_type {
+                    ColumnType::Bool => "INTEGER",
+                    t if t.is_integer() => "INTEGER",
+                    t if t.is_numeric() => "REAL",
+                    _ => "TEXT",
+                },
+                SqlDialect::Postgres => match &constraint.data_type {
+                    ColumnType::Utf8 | ColumnType::Categorical => return varchar(),
+                    ColumnType::Bool => "BOOLEAN",
+                    ColumnType::Date => "DATE",
+                    ColumnType::Datetime => "TIMESTAMP",
+                    ColumnType::Time => "TIME",
+                    ColumnType::Duration => "INTERVAL",
+                    ColumnType::Int8 | ColumnType::Int16 | ColumnType::UInt8 => "SMALLINT",
+                    ColumnType::Int32 | ColumnType::UInt16 => "INTEGER",
+                    ColumnType::Int64 | ColumnType::UInt32 | ColumnType::Integer => "BIGINT",
+                    ColumnType::UInt64 => "NUMERIC(20)",
+                    ColumnType::Float32 => "REAL",
+                    ColumnType::Float64 | ColumnType::Float => "DOUBLE PRECISION",
+                    ColumnType::Numeric => "NUMERIC",
+                    _ => "TEXT",
+                },
+                SqlDialect::Mysql => match &constraint.data_type {
+                    ColumnType::Utf8 | ColumnType::Categorical => return varchar(),
+                    ColumnType::Bool => "BOOLEAN",
+                    ColumnType::Date => "DATE",
+                    ColumnType::Datetime => "DATETIME",
+                    ColumnType::Time => "TIME",
+                    ColumnType::Int8 => "TINYINT",
+                    ColumnType::Int16 => "SMALLINT",
+                    ColumnType::Int32 => "INT",
+                    ColumnType::Int64 | ColumnType::Integer => "BIGINT",
+                    ColumnType::UInt8 => "TINYINT UNSIGNED",
+                    ColumnType::UInt16 => "SMALLINT UNSIGNED",
+                    ColumnType::UInt32 => "INT UNSIGNED",
+                    ColumnType::UInt64 => "BIGINT UNSIGNED",
+                    ColumnType::Float32 => "FLOAT",
+                    ColumnType::Float64 | ColumnType::Float | ColumnType::Numeric => "DOUBLE",
+                    _ => "TEXT",
+                },
+            };
+            String::from(sql_type)
+        }
+    }
+
+    fn _literal(value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+
+    /// Builds the `CHECK (...)` expression for an optional lower and upper bound.
+    fn _bounds_check<T: fmt::Display>(expr: &str, min: Option<T>, max: Option<T>) -> Option<String> {
+        match (min, max) {
+            (Some(min), Some(max)) => Some(format!("CHECK ({} BETWEEN {} AND {})", expr, min, max)),
+            (Some(min), None) => Some(format!("CHECK ({} >= {})", expr, min)),
+            (None, Some(max)) => Some(format!("CHECK ({} <= {})", expr, max)),
+            (None, None) => None,
+        }
+    }
+
+    impl ConstraintSet {
+        /// Renders the set as a `CREATE TABLE` statement with one column per
+        /// constraint and the checks inlined on each column.
+        pub fn to_sql(&self, table: &str, dialect: SqlDialect) -> String {
+            let columns: Vec<String> = self
+                .set
+                .iter()
+                .map(|constraint| {
+                    let column = dialect.quote(&constraint.name);
+                    let mut definition = vec![column.clone(), dialect.column_type(constraint)];
+                    if !constraint.nullable {
+                        definition.push(String::from("NOT NULL"));
+                    }
+                    if constraint.unique {
+                        definition.push(String::from("UNIQUE"));
+                    }
+                    let length_expr = format!("{}({})", dialect.length_fn(), column);
+                    definition.extend(_bounds_check(
+                        &length_expr,
+                        constraint.min_length,
+                        constraint.max_length,
+                    ));
+                    // inf and NaN have no SQL literal, so those bounds are left out.
+                    definition.extend(_bounds_check(
+                        &column,
+                        constraint.min_value.filter(|v| v.is_finite()),
+                        constraint.max_value.filter(|v| v.is_finite()),
+                    ));
+                    if let Some(range) = &constraint.value_range {
+                        let values: Vec<String> = range
+                            .split(", ")
+                            .filter(|v| *v != "null")
+                            .map(_literal)
+                            .collect();
+                        if !values.is_empty() {
+                            definition.push(format!("CHECK ({} IN ({}))", column, values.join(", ")));
+                        }
+                    }
+                    format!("    {}", definition.join(" "))
+                })
+                .collect();
+            format!(
+                "CREATE TABLE {} (\n{}\n);\n",
+                dialect.quote(table),
+                columns.join(",\n")
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constraints::constraints::*;
+    use crate::sql::sql::SqlDialect;
+    use polars::prelude::*;
+
+    #[test]
+    fn generate_sql() {
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let set = ConstraintSet::new(&df, "test");
+
+        let ddl = set.to_sql("brain_stroke", SqlDialect::Postgres);
+        assert!(ddl.starts_with("CREATE TABLE \"brain_stroke\" (\n"));
+        assert!(ddl.contains("\"age\" DOUBLE PRECISION CHECK (\"age\" BETWEEN 0.08 AND 82)"));
+        assert!(ddl.contains(
+            "\"Residence_type\" VARCHAR(5) NOT NULL CHECK (length(\"Residence_type\") BETWEEN 5 AND 5)"
+        ));
+        assert!(ddl.contains("'Urban'"));
+
+        let ddl = set.to_sql("brain_stroke", SqlDialect::Mysql);
+        assert!(ddl.contains("`age` DOUBLE CHECK (`age` BETWEEN 0.08 AND 82)"));
+        assert!(ddl.contains("CHAR_LENGTH(`gender`)"));
+
+        let ddl = set.to_sql("brain_stroke", SqlDialect::Sqlite);
+        assert!(ddl.contains("\"bmi\" REAL CHECK"));
+
+        let mut set = ConstraintSet::new(&df!("score" => &[1.0, 2.0]).unwrap(), "test");
+        set.set[0].min_value = Some(f64::NEG_INFINITY);
+        set.set[0].max_value = Some(f64::NAN);
+        assert!(!set.to_sql("scores", SqlDialect::Postgres).contains("CHECK (\"score\""));
+        set.set[0].min_value = Some(0.5);
+        assert!(set.to_sql("scores", SqlDialect::Postgres).contains("CHECK (\"score\" >= 0.5)"));
+    }
+}
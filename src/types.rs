@@ -0,0 +1,292 @@
+pub mod types {
+    use polars::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use std::fmt;
+    use std::str::FromStr;
+
+    /// Data type expected by a constraint. Covers the polars dtypes plus
+    /// logical groups that accept any member type.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+    #[serde(into = "String", try_from = "String")]
+    pub enum ColumnType {
+        Bool,
+        UInt8,
+        UInt16,
+        UInt32,
+        UInt64,
+        Int8,
+        Int16,
+        Int32,
+        Int64,
+        Float32,
+        Float64,
+        Utf8,
+        Binary,
+        Date,
+        Datetime,
+        Duration,
+        Time,
+        Categorical,
+        Null,
+        /// Any signed or unsigned integer.
+        Integer,
+        /// Any float.
+        Float,
+        /// Any integer or float.
+        Numeric,
+        /// Any data type.
+        Any,
+        /// A dtype without a dedicated variant, kept as its polars name.
+        Other(String),
+    }
+
+    impl ColumnType {
+        /// Bit width of integer types, signed or not.
+        fn int_width(&self) -> Option<(bool, u8)> {
+            match self {
+                ColumnType::Int8 => Some((true, 8)),
+                ColumnType::Int16 => Some((true, 16)),
+                ColumnType::Int32 => Some((true, 32)),
+                ColumnType::Int64 => Some((true, 64)),
+                ColumnType::UInt8 => Some((false, 8)),
+                ColumnType::UInt16 => Some((false, 16)),
+                ColumnType::UInt32 => Some((false, 32)),
+                ColumnType::UInt64 => Some((false, 64)),
+                _ => None,
+            }
+        }
+
+        pub fn is_integer(&self) -> bool {
+            self.int_width().is_some() || *self == ColumnType::Integer
+        }
+
+        pub fn is_float(&self) -> bool {
+            matches!(self, ColumnType::Float32 | ColumnType::Float64 | ColumnType::Float)
+        }
+
+        pub fn is_numeric(&self) -> bool {
+            self.is_integer() || self.is_float() || *self == ColumnType::Numeric
+        }
+
+        pub fn is_temporal(&self) -> bool {
+            matches!(
+                self,
+                ColumnType::Date | ColumnType::Datetime | ColumnType::Duration | ColumnType::Time
+            )
+        }
+
+        /// Whether data of type `actual` satisfies this constraint type.
+        /// Narrower integers fit wider ones (`i32` data satisfies `i64`),
+        /// unsigned integers fit wider signed ones, and floats accept
+        /// integers they can represent, since a float column whose values
+        /// happen to be whole numbers is read as integers. Groups accept
+        /// whatever one of their members accepts. All-null columns are only
+        /// accepted by `null` and `any`.
+        pub fn accepts(&self, actual: &ColumnType) -> bool {
+            if self == actual || *self == ColumnType::Any {
+                return true;
+            }
+            match (self, actual.int_width()) {
+                (_, None) if *actual == ColumnType::Null => false,
+                (ColumnType::Integer, Some(_)) => true,
+                (ColumnType::Numeric, _) => ColumnType::Integer.accepts(actual) || ColumnType::Float.accepts(actual),
+                (ColumnType::Float, _) => ColumnType::Float32.accepts(actual) || ColumnType::Float64.accepts(actual),
+                (ColumnType::Float64, Some(_)) => true,
+                (ColumnType::Float32, Some((_, width))) => width <= 16,
+                (expected, Some((signed, width))) => match expected.int_width() {
+                    Some((true, expected_width)) => {
+                        width < expected_width || (signed && width == expected_width)
+                    }
+                    Some((false, expected_width)) => !signed && width <= expected_width,
+                    None => false,
+                },
+                (expected, None) => match actual {
+                    ColumnType::Float32 => *expected == ColumnType::Float64,
+                    ColumnType::Categorical => *expected == ColumnType::Utf8,
+                    _ => false,
+                },
+            }
+        }
+
+        pub fn accepts_dtype(&self, dtype: &DataType) -> bool {
+            self.accepts(&ColumnType::from(dtype))
+        }
+
+        /// Polars dtype holding values of this type. Groups map to their
+        /// widest member; temporal types and `any` have no plain dtype.
+        pub fn to_dtype(&self) -> Option<DataType> {
+            Some(match self {
+                ColumnType::Bool => DataType::Boolean,
+                ColumnType::UInt8 => DataType::UInt8,
+                ColumnType::UInt16 => DataType::UInt16,
+                ColumnType::UInt32 => DataType::UInt32,
+                ColumnType::UInt64 => DataType::UInt64,
+                ColumnType::Int8 => DataType::Int8,
+                ColumnType::Int16 => DataType::Int16,
+                ColumnType::Int32 => DataType::Int32,
+                ColumnType::Int64 | ColumnType::Integer => DataType::Int64,
+                ColumnType::Float32 => DataType::Float32,
+                ColumnType::Float64 | ColumnType::Float | ColumnType::Numeric => DataType::Float64,
+                ColumnType::Utf8 | ColumnType::Categorical => DataType::Utf8,
+                _ => return None,
+            })
+        }
+    }
+
+    impl From<&DataType> for ColumnType {
+        fn from(dtype: &DataType) -> ColumnType {
+            match dtype {
+                DataType::Boolean => ColumnType::Bool,
+                DataType::UInt8 => ColumnType::UInt8,
+                DataType::UInt16 => ColumnType::UInt16,
+                DataType::UInt32 => ColumnType::UInt32,
+                DataType::UInt64 => ColumnType::UInt64,
+                DataType::Int8 => ColumnType::Int8,
+                DataType::Int16 => ColumnType::Int16,
+                DataType::Int32 => ColumnType::Int32,
+                DataType::Int64 => ColumnType::Int64,
+                DataType::Float32 => ColumnType::Float32,
+                DataType::Float64 => ColumnType::Float64,
+                DataType::Utf8 => ColumnType::Utf8,
+                DataType::Date => ColumnType::Date,
+                DataType::Datetime(_, _) => ColumnType::Datetime,
+                DataType::Duration(_) => ColumnType::Duration,
+                DataType::Time => ColumnType::Time,
+                DataType::Null => ColumnType::Null,
+                other => {
+                    let name = other.to_string();
+                    ColumnType::from_str(&name).unwrap_or(ColumnType::Other(name))
+                }
+            }
+        }
+    }
+
+    impl FromStr for ColumnType {
+        type Err = String;
+
+        /// Parses polars dtype names (`i64`, `str`, `datetime[μs]`) as well as
+        /// the friendlier names accepted by `ConstraintSet::modify`. Nested
+        /// polars types are kept by name; anything else is an error.
+        fn from_str(s: &str) -> Result<ColumnType, String> {
+            let name = s.split('[').next().unwrap_or_default().trim().to_lowercase();
+            Ok(match name.as_str() {
+                "bool" | "boolean" => ColumnType::Bool,
+                "u8" => ColumnType::UInt8,
+                "u16" => ColumnType::UInt16,
+                "u32" => ColumnType::UInt32,
+                "u64" => ColumnType::UInt64,
+                "i8" => ColumnType::Int8,
+                "i16" => ColumnType::Int16,
+                "i32" => ColumnType::Int32,
+                "i64" => ColumnType::Int64,
+                "f32" => ColumnType::Float32,
+                "f64" | "double" => ColumnType::Float64,
+                "str" | "string" | "utf8" => ColumnType::Utf8,
+                "binary" => ColumnType::Binary,
+                "date" => ColumnType::Date,
+                "datetime" => ColumnType::Datetime,
+                "duration" => ColumnType::Duration,
+                "time" => ColumnType::Time,
+                "cat" | "categorical" => ColumnType::Categorical,
+                "null" => ColumnType::Null,
+                "int" | "integer" => ColumnType::Integer,
+                "float" => ColumnType::Float,
+                "numeric" | "number" => ColumnType::Numeric,
+                "any" => ColumnType::Any,
+                "list" | "array" | "struct" | "object" | "decimal" | "unknown" => ColumnType::Other(String::from(s.trim())),
+                "" => return Err(String::from("Empty data type")),
+                _ => return Err(format!("Unknown data type: {}", s)),
+            })
+        }
+    }
+
+    impl TryFrom<String> for ColumnType {
+        type Error = String;
+
+        fn try_from(s: String) -> Result<ColumnType, String> {
+            ColumnType::from_str(&s)
+        }
+    }
+
+    impl From<ColumnType> for String {
+        fn from(column_type: ColumnType) -> String {
+            column_type.to_string()
+        }
+    }
+
+    impl fmt::Display for ColumnType {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = match self {
+                ColumnType::Bool => "bool",
+                ColumnType::UInt8 => "u8",
+                ColumnType::UInt16 => "u16",
+                ColumnType::UInt32 => "u32",
+                ColumnType::UInt64 => "u64",
+                ColumnType::Int8 => "i8",
+                ColumnType::Int16 => "i16",
+                ColumnType::Int32 => "i32",
+                ColumnType::Int64 => "i64",
+                ColumnType::Float32 => "f32",
+                ColumnType::Float64 => "f64",
+                ColumnType::Utf8 => "str",
+                ColumnType::Binary => "binary",
+                ColumnType::Date => "date",
+                ColumnType::Datetime => "datetime",
+                ColumnType::Duration => "duration",
+                ColumnType::Time => "time",
+                ColumnType::Categorical => "cat",
+                ColumnType::Null => "null",
+                ColumnType::Integer => "integer",
+                ColumnType::Float => "float",
+                ColumnType::Numeric => "numeric",
+                ColumnType::Any => "any",
+                ColumnType::Other(name) => name,
+            };
+            write!(f, "{}", name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::types::ColumnType;
+    use polars::prelude::*;
+
+    #[test]
+    fn type_compatibility() {
+        assert!(ColumnType::Int64.accepts(&ColumnType::Int32));
+        assert!(ColumnType::Int64.accepts(&ColumnType::UInt32));
+        assert!(!ColumnType::Int64.accepts(&ColumnType::UInt64));
+        assert!(!ColumnType::Int32.accepts(&ColumnType::Int64));
+        assert!(!ColumnType::UInt32.accepts(&ColumnType::Int8));
+        assert!(ColumnType::Float64.accepts(&ColumnType::Int64));
+        assert!(ColumnType::Float64.accepts(&ColumnType::Float32));
+        assert!(!ColumnType::Float32.accepts(&ColumnType::Float64));
+        assert!(!ColumnType::Int64.accepts(&ColumnType::Float64));
+        assert!(ColumnType::Integer.accepts(&ColumnType::UInt8));
+        assert!(!ColumnType::Integer.accepts(&ColumnType::Float32));
+        assert!(ColumnType::Numeric.accepts(&ColumnType::Float32));
+        assert!(!ColumnType::Numeric.accepts(&ColumnType::Utf8));
+        assert!(ColumnType::Float.accepts(&ColumnType::Int8));
+        assert!(ColumnType::Float.accepts(&ColumnType::Int64));
+        assert!(!ColumnType::Int64.accepts(&ColumnType::Null));
+        assert!(ColumnType::Any.accepts(&ColumnType::Null));
+        assert!(ColumnType::Utf8.accepts_dtype(&DataType::Utf8));
+        assert!(ColumnType::Datetime.accepts_dtype(&DataType::Datetime(TimeUnit::Milliseconds, None)));
+    }
+
+    #[test]
+    fn parse_type_names() {
+        assert_eq!("f64".parse::<ColumnType>(), Ok(ColumnType::Float64));
+        assert_eq!("str".parse::<ColumnType>(), Ok(ColumnType::Utf8));
+        assert_eq!("datetime[μs]".parse::<ColumnType>(), Ok(ColumnType::Datetime));
+        assert_eq!("int".parse::<ColumnType>(), Ok(ColumnType::Integer));
+        assert_eq!(
+            "list[i64]".parse::<ColumnType>(),
+            Ok(ColumnType::Other(String::from("list[i64]")))
+        );
+        assert!("integr".parse::<ColumnType>().is_err());
+        assert!(serde_json::from_str::<ColumnType>("\"flaot\"").is_err());
+        assert_eq!(ColumnType::from(&DataType::UInt16).to_string(), "u16");
+    }
+}
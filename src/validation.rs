@@ -2,15 +2,18 @@ pub mod validation {
     use crate::constraints::constraints::*;
     use polars::{export::num::ToPrimitive, prelude::*};
     use serde::{Deserialize, Serialize};
+    use std::collections::HashSet;
     use std::fmt;
     use std::fs::File;
     use std::path::Path;
     use csv::Writer;
-    use std::borrow::Cow;
 
     #[derive(Serialize, Deserialize, Debug)]
     pub struct Validation {
         pub name: String,
+        /// Column of the data checked, when it was matched through an alias
+        /// or ignoring case.
+        pub matched_column: Option<String>,
         pub data_type: bool,
         pub nullable: Option<u32>,
         pub unique: Option<u32>,
@@ -19,143 +22,340 @@ pub mod validation {
         pub min_value: Option<u32>,
         pub max_value: Option<u32>,
         pub value_range: Option<u32>,
+        pub suggested_type: Option<u32>,
+        pub semantic_type: Option<u32>,
+        pub precision: Option<u32>,
+        pub scale: Option<u32>,
+        pub integer_only: Option<u32>,
+        pub positive: Option<u32>,
+        pub non_negative: Option<u32>,
+        pub multiple_of: Option<u32>,
+        pub z_score: Option<u32>,
+        pub iqr: Option<u32>,
+        pub mean: Option<bool>,
+        pub median: Option<bool>,
+        pub sum: Option<bool>,
+        pub std_dev: Option<bool>,
+        pub distinct_count: Option<bool>,
+        pub null_count: Option<bool>,
+        pub monotonic: Option<u32>,
+        /// Numbers missing from a gap-free sequence.
+        pub gaps: Option<u32>,
+        /// Whether the gap check could run, `false` when the column isn't
+        /// whole numbers.
+        pub gaps_applicable: Option<bool>,
+        pub trimmed: Option<u32>,
+        pub case: Option<u32>,
+        pub ascii_only: Option<u32>,
+        pub allowed_chars: Option<u32>,
+        pub control_chars: Option<u32>,
+        /// Line numbers where the expected order breaks, counting the header
+        /// as line 1. Not written to the csv output.
+        #[serde(skip)]
+        pub order_breaks: Vec<usize>,
     }
 
-    impl Validation {
-        fn _check_data_type(data: &DataFrame, constraint: &Constraint) -> bool {
-            if let Ok(col) = data.column(&constraint.name) {
-                col.dtype().to_string() == constraint.data_type
-            } else {
-                false
-            }
+    fn _texts(col: &Series) -> Vec<Option<String>> {
+        col.cast(&DataType::Utf8)
+            .ok()
+            .and_then(|col| col.utf8().ok().map(|s| s.into_iter().map(|v| v.map(String::from)).collect()))
+            .unwrap_or_else(|| vec![None; col.len()])
+    }
+
+    fn _numbers(col: &Series) -> Option<Vec<Option<f64>>> {
+        if !col.dtype().is_numeric() {
+            return None;
         }
+        Some(col.cast(&DataType::Float64).ok()?.f64().ok()?.into_iter().collect())
+    }
+
+    /// Marks the non-null values failing `check`.
+    fn _mask<T, F: Fn(&T) -> bool>(values: &[Option<T>], check: F) -> Vec<bool> {
+        values
+            .iter()
+            .map(|v| v.as_ref().is_some_and(|v| !check(v)))
+            .collect()
+    }
+
+    /// Rows failing each value-level check of a constraint, as one mask
+    /// per enabled check. Nulls only fail `nullable`. Checks on the column
+    /// as a whole, like aggregates, data type and gaps, have no failing
+    /// rows. Validation counts and rejected rows both come from here.
+    pub fn row_failures(data: &DataFrame, constraint: &Constraint) -> Vec<(&'static str, Vec<bool>)> {
+        let col = match data.column(&constraint.name) {
+            Ok(col) => col,
+            Err(_) => return vec![],
+        };
+        let texts = _texts(col);
+        let numbers = _numbers(col);
+        let strings = (col.dtype() == &DataType::Utf8).then_some(&texts);
+        let mut failures: Vec<(&'static str, Vec<bool>)> = vec![];
 
-        fn _check_nullable(data: &DataFrame, constraint: &Constraint) -> Option<u32> {
-            if !constraint.nullable {
-                if let Ok(col) = data.column(&constraint.name) {
-                    col.null_count().to_u32()
-                } else {
-                    return None
+        if !constraint.nullable {
+            failures.push(("nullable", col.is_null().into_iter().map(|v| v.unwrap_or_default()).collect()));
+        }
+        if constraint.unique {
+            if let Ok(duplicated) = col.is_duplicated() {
+                let duplicated = duplicated & col.is_not_null();
+                failures.push(("unique", duplicated.into_iter().map(|v| v.unwrap_or_default()).collect()));
+            }
+        }
+        if let Some(strings) = strings {
+            if let Some(min) = constraint.min_length {
+                failures.push(("min_length", _mask(strings, |v| v.chars().count() as u32 >= min)));
+            }
+            if let Some(max) = constraint.max_length {
+                failures.push(("max_length", _mask(strings, |v| v.chars().count() as u32 <= max)));
+            }
+            if constraint.trimmed {
+                failures.push(("trimmed", _mask(strings, |v| v.trim() == v)));
+            }
+            if let Some(case) = constraint.case {
+                failures.push(("case", _mask(strings, |v| case.matches(v))));
+            }
+            if constraint.ascii_only {
+                failures.push(("ascii_only", _mask(strings, |v| v.is_ascii())));
+            }
+            if let Some(class) = constraint.allowed_chars.as_deref() {
+                if let Some(pattern) = allowed_chars_pattern(class) {
+                    failures.push(("allowed_chars", _mask(strings, |v| pattern.is_match(v))));
                 }
-            } else {
-                return None
+            }
+            if constraint.no_control_chars {
+                failures.push(("control_chars", _mask(strings, |v| !v.chars().any(char::is_control))));
             }
         }
-
-        fn _check_duplicates(data: &DataFrame, constraint: &Constraint) -> Option<u32> {
-            if !constraint.unique {
-                return None;
-            } else {
-                let col = data.column(&constraint.name);
-                return match col {
-                    Ok(s) => s.is_unique().iter().count().to_u32(),
-                    Err(_) => None,
-                };
+        if let Some(numbers) = &numbers {
+            if let Some(min) = constraint.min_value {
+                failures.push(("min_value", _mask(numbers, |v| *v >= min)));
+            }
+            if let Some(max) = constraint.max_value {
+                failures.push(("max_value", _mask(numbers, |v| *v <= max)));
+            }
+            if constraint.integer_only {
+                failures.push(("integer_only", _mask(numbers, |v| v.fract() == 0.0)));
+            }
+            if constraint.positive {
+                failures.push(("positive", _mask(numbers, |v| *v > 0.0)));
+            }
+            if constraint.non_negative {
+                failures.push(("non_negative", _mask(numbers, |v| *v >= 0.0)));
+            }
+            if let Some(step) = constraint.multiple_of {
+                failures.push(("multiple_of", _mask(numbers, |v| is_multiple_of(*v, step))));
+            }
+            if let (Some(stats), Some(z_score)) = (&constraint.stats, constraint.z_score) {
+                if stats.std_dev > 0.0 {
+                    failures.push(("z_score", _mask(numbers, |v| ((v - stats.mean) / stats.std_dev).abs() <= z_score)));
+                }
+            }
+            if let (Some(stats), Some(factor)) = (&constraint.stats, constraint.iqr_factor) {
+                let iqr = stats.q3 - stats.q1;
+                failures.push(("iqr", _mask(numbers, |v| {
+                    *v >= stats.q1 - factor * iqr && *v <= stats.q3 + factor * iqr
+                })));
             }
         }
-
-        fn _check_min_length(data: &DataFrame, constraint: &Constraint) -> Option<u32> {
-            let col = data.column(&constraint.name);
-            let min_length = match col {
-                Ok(s) => s.utf8().map(|s| s.str_n_chars()),
-                Err(e) => Err(e),
-            };
-            match min_length {
-                Ok(s) => s
-                    .lt(constraint.min_length.unwrap_or_default())
-                    .into_iter()
-                    .filter(|b| b.unwrap_or_default() == true)
-                    .count()
-                    .to_u32(),
-                Err(_) => None,
+        if let Some(range) = constraint.value_range.as_deref() {
+            let values: HashSet<&str> = range.split(", ").collect();
+            failures.push(("value_range", _mask(&texts, |v| values.contains(v.as_str()))));
+        }
+        if let Some(suggested_type) = &constraint.suggested_type {
+            failures.push(("suggested_type", _mask(&texts, |v| parses_as(v, suggested_type))));
+        }
+        if let Some(semantic_type) = constraint.semantic_type {
+            failures.push(("semantic_type", _mask(&texts, |v| semantic_type.matches(v))));
+        }
+        if constraint.precision.is_some() || constraint.scale.is_some() {
+            let digits: Vec<Option<(u32, u32)>> = decimal_texts(col)
+                .unwrap_or_default()
+                .iter()
+                .map(|v| v.as_deref().and_then(decimal_digits))
+                .collect();
+            if let Some(limit) = constraint.precision {
+                failures.push(("precision", _mask(&digits, |(precision, _)| *precision <= limit)));
+            }
+            if let Some(limit) = constraint.scale {
+                failures.push(("scale", _mask(&digits, |(_, scale)| *scale <= limit)));
             }
         }
-        fn _check_max_length(data: &DataFrame, constraint: &Constraint) -> Option<u32> {
-            let col = data.column(&constraint.name);
-            let max_length = match col {
-                Ok(s) => s.utf8().map(|s| s.str_n_chars()),
-                Err(e) => Err(e),
-            };
-            match max_length {
-                Ok(s) => s
-                    .gt(constraint.max_length.unwrap_or_default())
-                    .into_iter()
-                    .filter(|b| b.unwrap_or_default() == true)
-                    .count()
-                    .to_u32(),
-                Err(_) => None,
+        if let Some(monotonic) = constraint.monotonic {
+            let mut mask = vec![false; col.len()];
+            for row in monotonic.breaks(col) {
+                mask[row] = true;
             }
+            failures.push(("monotonic", mask));
         }
+        failures
+    }
 
-        fn _check_min_value(data: &DataFrame, constraint: &Constraint) -> Option<u32> {
+    impl Validation {
+        fn _check_data_type(data: &DataFrame, constraint: &Constraint) -> bool {
             if let Ok(col) = data.column(&constraint.name) {
-                col.lt(constraint.min_value.unwrap_or_default())
-                .unwrap_or_default()
-                .sum()
+                // An all-null column satisfies any nullable constraint.
+                constraint.data_type.accepts_dtype(col.dtype()) || (constraint.nullable && col.dtype() == &DataType::Null)
             } else {
-                return None
+                false
             }
         }
 
-        fn _check_max_value(data: &DataFrame, constraint: &Constraint) -> Option<u32> {
-            if let Ok(col) = data.column(&constraint.name) {
-                col.gt(constraint.max_value.unwrap_or_default())
-                .unwrap_or_default()
-                .sum()
-            } else {
-                return None
+        fn _check_gaps(data: &DataFrame, constraint: &Constraint) -> Option<u32> {
+            if !constraint.no_gaps {
+                return None;
             }
+            let col = data.column(&constraint.name).ok()?;
+            sequence_gaps(col)?
+                .iter()
+                .map(|(first, last)| last - first + 1)
+                .sum::<i64>()
+                .to_u32()
         }
 
-        fn _check_value_range(data: &DataFrame, constraint: &Constraint) -> Option<u32> {
-            let col = data.column(&constraint.name);
-            let ranges_string = constraint.value_range.as_ref().map(|s| s.as_str());
-            if ranges_string.is_none() {
-                return None;
+        /// Whether a column aggregate falls in its expected range.
+        fn _check_aggregate(data: &DataFrame, constraint: &Constraint, name: &str) -> Option<bool> {
+            let range = constraint.aggregates.range(name)?;
+            let col = data.column(&constraint.name).ok()?;
+            Some(aggregate(col, name).is_some_and(|v| range.contains(v)))
+        }
+
+        /// Aggregate checks with their outcome, skipping the unconstrained ones.
+        pub fn aggregate_checks(&self) -> Vec<(&'static str, bool)> {
+            [
+                ("mean", self.mean),
+                ("median", self.median),
+                ("sum", self.sum),
+                ("std_dev", self.std_dev),
+                ("distinct_count", self.distinct_count),
+                ("null_count", self.null_count),
+            ]
+            .into_iter()
+            .filter_map(|(name, passed)| passed.map(|p| (name, p)))
+            .collect()
+        }
+
+        /// Failing checks with their number of failed values, `None` for
+        /// checks that pass or fail as a whole.
+        pub fn failures(&self) -> Vec<(&'static str, Option<u32>)> {
+            let mut failures: Vec<(&'static str, Option<u32>)> = vec![];
+            if !self.data_type {
+                failures.push(("data_type", None));
             }
-            let ranges: Cow<'_, [String]> = match ranges_string {
-                Some(s) => Cow::from(s.split(", ").map(str::to_string).collect::<Vec<_>>()),
-                None => Cow::from(vec![String::from("null")]),
-            };
-            match col {
-                Ok(s) => s
-                    .is_in(&Series::new("ranges", &ranges))
-                    .map(|b| !b)
-                    .unwrap_or_default()
-                    .sum(),
-                Err(_) => None,
+            if self.gaps_applicable == Some(false) {
+                failures.push(("gaps_not_applicable", None));
+            }
+            let counts = [
+                ("nullable", self.nullable),
+                ("unique", self.unique),
+                ("min_length", self.min_length),
+                ("max_length", self.max_length),
+                ("min_value", self.min_value),
+                ("max_value", self.max_value),
+                ("value_range", self.value_range),
+            ];
+            for (check, count) in counts.into_iter().chain(self.extra_checks()) {
+                if count.unwrap_or_default() > 0 {
+                    failures.push((check, count));
+                }
             }
+            for (check, passed) in self.aggregate_checks() {
+                if !passed {
+                    failures.push((check, None));
+                }
+            }
+            failures
+        }
+
+        /// Checks that are reported below the main validation table.
+        pub fn extra_checks(&self) -> Vec<(&'static str, Option<u32>)> {
+            vec![
+                ("suggested_type", self.suggested_type),
+                ("semantic_type", self.semantic_type),
+                ("precision", self.precision),
+                ("scale", self.scale),
+                ("integer_only", self.integer_only),
+                ("positive", self.positive),
+                ("non_negative", self.non_negative),
+                ("multiple_of", self.multiple_of),
+                ("z_score", self.z_score),
+                ("iqr", self.iqr),
+                ("monotonic", self.monotonic),
+                ("gaps", self.gaps),
+                ("trimmed", self.trimmed),
+                ("case", self.case),
+                ("ascii_only", self.ascii_only),
+                ("allowed_chars", self.allowed_chars),
+                ("control_chars", self.control_chars),
+            ]
         }
 
         pub fn new(data: &DataFrame, constraint: &Constraint) -> Validation {
-            return Validation {
+            let masks = row_failures(data, constraint);
+            let rows = |check: &str| -> Option<Vec<usize>> {
+                let (_, mask) = masks.iter().find(|(name, _)| *name == check)?;
+                Some(mask.iter().enumerate().filter(|(_, failed)| **failed).map(|(row, _)| row).collect())
+            };
+            let count = |check: &str| rows(check).and_then(|rows| rows.len().to_u32());
+            let order_breaks = rows("monotonic");
+            Validation {
                 name: String::from(&constraint.name),
+                matched_column: None,
                 data_type: Self::_check_data_type(data, constraint),
-                nullable: Self::_check_nullable(data, constraint),
-                unique: Self::_check_duplicates(data, constraint),
-                min_length: Self::_check_min_length(data, constraint),
-                max_length: Self::_check_max_length(data, constraint),
-                min_value: Self::_check_min_value(data, constraint),
-                max_value: Self::_check_max_value(data, constraint),
-                value_range: Self::_check_value_range(data, constraint),
-            };
+                nullable: count("nullable"),
+                unique: count("unique"),
+                min_length: count("min_length"),
+                max_length: count("max_length"),
+                min_value: count("min_value"),
+                max_value: count("max_value"),
+                value_range: count("value_range"),
+                suggested_type: count("suggested_type"),
+                semantic_type: count("semantic_type"),
+                precision: count("precision"),
+                scale: count("scale"),
+                integer_only: count("integer_only"),
+                positive: count("positive"),
+                non_negative: count("non_negative"),
+                multiple_of: count("multiple_of"),
+                z_score: count("z_score"),
+                iqr: count("iqr"),
+                mean: Self::_check_aggregate(data, constraint, "mean"),
+                median: Self::_check_aggregate(data, constraint, "median"),
+                sum: Self::_check_aggregate(data, constraint, "sum"),
+                std_dev: Self::_check_aggregate(data, constraint, "std_dev"),
+                distinct_count: Self::_check_aggregate(data, constraint, "distinct_count"),
+                null_count: Self::_check_aggregate(data, constraint, "null_count"),
+                monotonic: order_breaks.as_ref().and_then(|rows| rows.len().to_u32()),
+                gaps: Self::_check_gaps(data, constraint),
+                gaps_applicable: constraint
+                    .no_gaps
+                    .then(|| data.column(&constraint.name).ok().and_then(sequence_gaps).is_some()),
+                trimmed: count("trimmed"),
+                case: count("case"),
+                ascii_only: count("ascii_only"),
+                allowed_chars: count("allowed_chars"),
+                control_chars: count("control_chars"),
+                order_breaks: order_breaks
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|row| row + 2)
+                    .collect(),
+            }
         }
     }
 
     impl fmt::Display for Validation {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             let name_length = self.name.len().to_usize().unwrap_or_default();
-            let range_string = self.value_range.clone().unwrap_or_default().to_string();
+            let range_string = self.value_range.unwrap_or_default().to_string();
 
             let trimmed_range = if range_string.len() > 60 {
                 &range_string[..60]
             } else {
                 &range_string
             };
-            write!(f, "+{:<}+\n", "-".repeat(name_length + 178)).unwrap_or_default();
-            write!(
+            writeln!(f, "+{:<}+", "-".repeat(name_length + 178)).unwrap_or_default();
+            writeln!(
                 f,
-                "|{:<width1$}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<width2$}|\n",
+                "|{:<width1$}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<}\t| {:<width2$}|",
                 "Name",
                 "Data Type",
                 "Nullable",
@@ -168,10 +368,10 @@ pub mod validation {
                 width1 = name_length,
                 width2 = 60
             ).unwrap_or_default();
-            write!(f, "+{:<}+\n", "-".repeat(name_length + 178)).unwrap_or_default();
-            write!(
+            writeln!(f, "+{:<}+", "-".repeat(name_length + 178)).unwrap_or_default();
+            writeln!(
                 f,
-                "|{:<width1$}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<60}|\n",
+                "|{:<width1$}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<60}|",
                 self.name,
                 self.data_type,
                 self.nullable.unwrap_or_default(),
@@ -183,30 +383,239 @@ pub mod validation {
                 trimmed_range, 
                 width1 = name_length,
                 ).unwrap_or_default();
-            write!(f, "+{:<}+\n", "-".repeat(name_length + 178))
+            writeln!(f, "+{:<}+", "-".repeat(name_length + 178))
+
+        }
+    }
+
+    /// Results of the table-level checks. Each check is `None` when it isn't
+    /// constrained, otherwise whether it passed.
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct TableValidation {
+        pub row_count: u32,
+        pub column_count: u32,
+        pub file_size: Option<u64>,
+        pub expected_rows: Option<u32>,
+        pub min_rows: Option<bool>,
+        pub max_rows: Option<bool>,
+        pub columns: Option<bool>,
+        /// File size checks, `None` when the data didn't come from a file.
+        pub min_file_size: Option<bool>,
+        pub max_file_size: Option<bool>,
+        pub row_count_matches: Option<bool>,
+        /// Whether the columns matched by alias or ignoring case could be
+        /// renamed to their constraint names, `None` when none were.
+        pub column_names: Option<bool>,
+        /// Number of duplicated rows, `None` when unchecked or when a column
+        /// of the check is missing.
+        pub duplicate_rows: Option<u32>,
+        /// Line numbers of duplicated rows paired with the line of their
+        /// first occurrence, counting the header as line 1.
+        pub duplicates: Vec<(usize, usize)>,
+    }
+
+    /// Last number in a text, used for control files and trailer lines.
+    fn _last_number(text: &str) -> Option<u32> {
+        text.split(|c: char| !c.is_ascii_digit())
+            .rfind(|s| !s.is_empty())
+            .and_then(|s| s.parse().ok())
+    }
+
+    impl TableValidation {
+        fn _expected_rows(source: &RowCountSource, file_path: Option<&str>) -> Option<u32> {
+            match source {
+                RowCountSource::ControlFile(path) => _last_number(&std::fs::read_to_string(path).ok()?),
+                RowCountSource::Trailer => {
+                    let contents = std::fs::read_to_string(file_path?).ok()?;
+                    _last_number(contents.lines().rev().find(|l| !l.trim().is_empty())?)
+                }
+            }
+        }
+
+        /// Checks the table against its constraints. File size and trailer
+        /// checks need the path of the data file, which should be read with
+        /// `ConstraintSet::read_csv` to leave the trailer out of the rows.
+        pub fn new(data: &DataFrame, table: &TableConstraint, file_path: Option<&str>) -> TableValidation {
+            let row_count = data.height().to_u32().unwrap_or_default();
+            let column_count = data.width().to_u32().unwrap_or_default();
+            let file_size = file_path
+                .and_then(|p| std::fs::metadata(p).ok())
+                .map(|m| m.len());
+            let duplicates: Option<Vec<(usize, usize)>> = table
+                .duplicate_rows
+                .as_ref()
+                .and_then(|check| check.find(data).ok())
+                .map(|duplicates| duplicates.into_iter().map(|(row, first)| (row + 2, first + 2)).collect());
+            let expected_rows = table
+                .expected_rows_from
+                .as_ref()
+                .and_then(|source| Self::_expected_rows(source, file_path));
+            TableValidation {
+                row_count,
+                column_count,
+                file_size,
+                expected_rows,
+                min_rows: table.min_rows_bound().map(|min| row_count >= min),
+                max_rows: table.max_rows_bound().map(|max| row_count <= max),
+                columns: table.column_count.map(|count| column_count == count),
+                min_file_size: file_size.and_then(|size| table.min_file_size.map(|min| size >= min)),
+                max_file_size: file_size.and_then(|size| table.max_file_size.map(|max| size <= max)),
+                row_count_matches: table
+                    .expected_rows_from
+                    .as_ref()
+                    .map(|_| expected_rows == Some(row_count)),
+                column_names: None,
+                duplicate_rows: duplicates.as_ref().and_then(|d| d.len().to_u32()),
+                duplicates: duplicates.unwrap_or_default(),
+            }
+        }
 
+        /// Counts and check outcomes by name, for the csv output.
+        pub fn results(&self) -> Vec<(&'static str, String)> {
+            let mut results = vec![
+                ("row_count", self.row_count.to_string()),
+                ("column_count", self.column_count.to_string()),
+            ];
+            if let Some(file_size) = self.file_size {
+                results.push(("file_size", file_size.to_string()));
+            }
+            if let Some(expected_rows) = self.expected_rows {
+                results.push(("expected_rows", expected_rows.to_string()));
+            }
+            results.extend(self.checks().into_iter().map(|(check, passed)| (check, passed.to_string())));
+            results
+        }
+
+        /// Checks with their outcome, skipping the unconstrained ones.
+        pub fn checks(&self) -> Vec<(&'static str, bool)> {
+            [
+                ("min_rows", self.min_rows),
+                ("max_rows", self.max_rows),
+                ("column_count", self.columns),
+                ("min_file_size", self.min_file_size),
+                ("max_file_size", self.max_file_size),
+                ("row_count_matches", self.row_count_matches),
+                ("column_names", self.column_names),
+                ("duplicate_rows", self.duplicate_rows.map(|count| count == 0)),
+            ]
+            .into_iter()
+            .filter_map(|(name, passed)| passed.map(|p| (name, p)))
+            .collect()
+        }
+    }
+
+    impl ConstraintSet {
+        /// Reads a csv file to validate against the set. When the expected
+        /// row count comes from a trailer, the last line is left out so it
+        /// isn't parsed as a data row.
+        pub fn read_csv(&self, file_path: &str) -> PolarsResult<DataFrame> {
+            if self.table.expected_rows_from != Some(RowCountSource::Trailer) {
+                return CsvReader::from_path(file_path)?.finish();
+            }
+            let contents = std::fs::read_to_string(file_path)?;
+            let rows = contents.trim_end().rsplit_once('\n').map_or("", |(rows, _)| rows);
+            CsvReader::new(std::io::Cursor::new(rows.as_bytes())).finish()
+        }
+    }
+
+    /// A failed check with the severity given by its constraint.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Finding {
+        /// Column name, or `table` for table checks.
+        pub column: String,
+        pub check: String,
+        /// Number of failed values, for checks counted per value.
+        pub failures: Option<u32>,
+        pub severity: Severity,
+    }
+
+    impl fmt::Display for Finding {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self.failures {
+                Some(count) => write!(f, "{}: {} failed for {} values", self.column, self.check, count),
+                None => write!(f, "{}: {} failed", self.column, self.check),
+            }
         }
     }
 
     #[derive(Serialize, Deserialize, Debug)]
     pub struct ValidationSet {
+        /// Name of the constraint set.
         pub name: String,
+        /// File that was validated.
+        pub source: Option<String>,
+        /// Metadata of the constraint set.
+        pub metadata: Metadata,
         pub set: Vec<Validation>,
+        pub table: TableValidation,
+        pub findings: Vec<Finding>,
     }
 
     impl ValidationSet {
         pub fn new(data: &DataFrame, constraint_set: &ConstraintSet) -> ValidationSet {
-            //let columns: Vec<&str> = data.get_column_names();
+            Self::validate(data, constraint_set, None)
+        }
+
+        /// Validates data read from `file_path`, enabling the file-level checks.
+        pub fn for_file(data: &DataFrame, constraint_set: &ConstraintSet, file_path: &str) -> ValidationSet {
+            Self::validate(data, constraint_set, Some(file_path))
+        }
+
+        fn validate(data: &DataFrame, constraint_set: &ConstraintSet, file_path: Option<&str>) -> ValidationSet {
+            let matches = constraint_set.match_columns(data);
+            let (aligned, renamed) = match constraint_set.align_columns(data) {
+                Ok(aligned) => (aligned, true),
+                Err(_) => (data.clone(), false),
+            };
+            let mut table = TableValidation::new(&aligned, &constraint_set.table, file_path);
+            table.column_names = constraint_set
+                .set
+                .iter()
+                .zip(&matches)
+                .any(|(c, matched)| matched.as_ref().is_some_and(|m| *m != c.name))
+                .then_some(renamed);
+            let mut findings: Vec<Finding> = table
+                .checks()
+                .into_iter()
+                .filter(|(_, passed)| !passed)
+                .map(|(check, _)| Finding {
+                    column: String::from("table"),
+                    check: String::from(check),
+                    failures: None,
+                    severity: constraint_set.table.severity_of(check),
+                })
+                .collect();
             let mut validation_set: Vec<Validation> = vec![];
-            for c in &constraint_set.set {
-                let validation = Validation::new(&data, &c);
+            for (c, matched) in constraint_set.set.iter().zip(matches) {
+                let mut validation = Validation::new(&aligned, c);
+                validation.matched_column = matched.filter(|column| *column != c.name);
+                for (check, failures) in validation.failures() {
+                    findings.push(Finding {
+                        column: String::from(&c.name),
+                        check: String::from(check),
+                        failures,
+                        severity: c.severity_of(check),
+                    });
+                }
                 validation_set.push(validation)
             }
-            return ValidationSet {
-                name: String::from("XXX"),
+            findings.sort_by_key(|finding| finding.severity);
+            ValidationSet {
+                name: constraint_set.name.clone(),
+                source: file_path.map(String::from),
+                metadata: constraint_set.metadata.clone(),
                 set: validation_set,
+                table,
+                findings,
             }
         }
+
+        /// Whether any check with error severity failed.
+        pub fn has_errors(&self) -> bool {
+            self.findings.iter().any(|finding| finding.severity == Severity::Error)
+        }
+        /// Saves a row per column validation to `filepath`, and the table
+        /// results to a `.table.csv` file next to it.
         pub fn save_csv<T: AsRef<Path>>(&self, filepath: T) -> Result<(), String> {
             let file_path = filepath.as_ref();
 
@@ -224,23 +633,37 @@ pub mod validation {
             for validation in &self.set {
                 writer.serialize(validation).map_err(|e| format!("Failed to serialize data: {}", e))?;
             }
+
+            let table_path = file_path.with_extension("table.csv");
+            let file = File::create(&table_path).map_err(|e| format!("Failed to create file: {}", e))?;
+            let mut writer = Writer::from_writer(file);
+            writer.write_record(["check", "value"]).map_err(|e| format!("Failed to serialize data: {}", e))?;
+            for (check, value) in self.table.results() {
+                writer.write_record([check, value.as_str()]).map_err(|e| format!("Failed to serialize data: {}", e))?;
+            }
             Ok(())
         }
     }
     impl fmt::Display for ValidationSet {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match &self.source {
+                Some(source) => writeln!(f, "Validation of {} against {}", source, self.name),
+                None => writeln!(f, "Validation against {}", self.name),
+            }
+            .unwrap_or_default();
+            write!(f, "{}", self.metadata).unwrap_or_default();
             let mut name_length: usize = 0;
             for validation in &self.set {
                 if validation.name.len().to_usize().unwrap_or_default() > name_length {
                     name_length = validation.name.len().to_usize().unwrap_or_default()
                 }
             }
-            write!(f, "+{:<}{:<}+\n",
+            writeln!(f, "+{:<}{:<}+",
                 "-".repeat(name_length),
                 "-".repeat(149-name_length)).unwrap_or_default();
-            write!(
+            writeln!(
                 f,
-                "| {:<width1$}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10} |\n",
+                "| {:<width1$}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10}\t| {:<10} |",
                 "Name",
                 "Data Type",
                 "Nullable",
@@ -253,13 +676,13 @@ pub mod validation {
                 width1 = name_length,
             )
             .unwrap_or_default();
-            write!(f, "+{:<}{:<}+\n",
+            writeln!(f, "+{:<}{:<}+",
                 "-".repeat(name_length),
                 "-".repeat(149-name_length)).unwrap_or_default();
             for validation in &self.set {
-               write!(
+               writeln!(
                     f,
-                    "| {:<width1$}\t| {:<11}\t| {:<11}\t| {:<11}\t| {:<11}\t| {:<11}\t| {:<11}\t| {:<11}\t| {:<11} |\n",
+                    "| {:<width1$}\t| {:<11}\t| {:<11}\t| {:<11}\t| {:<11}\t| {:<11}\t| {:<11}\t| {:<11}\t| {:<11} |",
                     validation.name,
                     validation.data_type,
                     validation.nullable.unwrap_or_default(),
@@ -271,19 +694,50 @@ pub mod validation {
                     validation.value_range.unwrap_or_default(),
                     width1 = name_length,
                 ).unwrap_or_default();
-                write!(f, "+{:<}{:<}+\n",
+                writeln!(f, "+{:<}{:<}+",
                     "-".repeat(name_length),
                     "-".repeat(149-name_length)).unwrap_or_default();
             }
+            writeln!(f, "Rows: {}, Columns: {}", self.table.row_count, self.table.column_count).unwrap_or_default();
+            for (line, first_line) in self.table.duplicates.iter().take(10) {
+                writeln!(f, "table: line {} duplicates line {}", line, first_line).unwrap_or_default();
+            }
+            if self.table.duplicates.len() > 10 {
+                writeln!(f, "table: {} more duplicated rows", self.table.duplicates.len() - 10).unwrap_or_default();
+            }
+            for validation in &self.set {
+                if let Some(column) = &validation.matched_column {
+                    writeln!(f, "{}: matched column {}", validation.name, column).unwrap_or_default();
+                }
+                for line in validation.order_breaks.iter().take(10) {
+                    writeln!(f, "{}: order breaks at line {}", validation.name, line).unwrap_or_default();
+                }
+            }
+            for (severity, title) in [
+                (Severity::Error, "Errors"),
+                (Severity::Warning, "Warnings"),
+                (Severity::Info, "Info"),
+            ] {
+                let findings: Vec<&Finding> = self.findings.iter().filter(|finding| finding.severity == severity).collect();
+                if !findings.is_empty() {
+                    writeln!(f, "{}:", title).unwrap_or_default();
+                }
+                for finding in findings {
+                    writeln!(f, "  {}", finding).unwrap_or_default();
+                }
+            }
             Ok(())
         }
     }
 }
 
+#[cfg(test)]
 mod tests {
-    use crate::constraints::constraints::Constraint;
-    use crate::validation::validation::Validation;
-    use polars::{export::num::ToPrimitive, prelude::*};
+    use crate::constraints::constraints::{
+        ColumnType, Constraint, ConstraintSet, DuplicateRows, InferenceOptions, NameMatching, Severity,
+    };
+    use crate::validation::validation::{Validation, ValidationSet};
+    use polars::prelude::*;
 
     #[test]
     fn test_validations() {
@@ -321,4 +775,289 @@ mod tests {
         assert_eq!(validation.max_value, Some(2));
 
     }
+
+    #[test]
+    fn test_suggested_type() {
+        let df_good = df!("amount" => &["1.5", "2", "3", "4", "5"]).unwrap();
+        let df_bad = df!("amount" => &[Some("1.5"), Some("two"), None, Some("4"), Some("n/a")]).unwrap();
+
+        let constraint = Constraint::new(&df_good, "amount");
+        assert_eq!(constraint.suggested_type, Some(ColumnType::Float64));
+        let validation = Validation::new(&df_bad, &constraint);
+        assert_eq!(validation.suggested_type, Some(2));
+        let validation = Validation::new(&df_good, &constraint);
+        assert_eq!(validation.suggested_type, Some(0));
+    }
+
+    #[test]
+    fn test_semantic_type() {
+        let df_good = df!("email" => &["a@example.com", "b@example.org", "c@example.net"]).unwrap();
+        let df_bad = df!("email" => &[Some("a@example.com"), Some("b at example.org"), None]).unwrap();
+
+        let constraint = Constraint::new(&df_good, "email");
+        let validation = Validation::new(&df_bad, &constraint);
+        assert_eq!(validation.semantic_type, Some(1));
+    }
+
+    #[test]
+    fn test_precision_and_scale() {
+        let df_good = df!("amount" => &[12.5, 1000.25, 0.1]).unwrap();
+        let df_bad = df!("amount" => &[12.505, 123456.25, 0.1 + 0.2]).unwrap();
+
+        let constraint = Constraint::new(&df_good, "amount");
+        assert_eq!((constraint.precision, constraint.scale), (Some(6), Some(2)));
+        let validation = Validation::new(&df_bad, &constraint);
+        assert_eq!(validation.scale, Some(2));
+        assert_eq!(validation.precision, Some(2));
+        let validation = Validation::new(&df_good, &constraint);
+        assert_eq!((validation.precision, validation.scale), (Some(0), Some(0)));
+    }
+
+    #[test]
+    fn test_integer_and_sign() {
+        let df_good = df!("count" => &[10.0, 25.0, 5.0]).unwrap();
+        let df_bad = df!("count" => &[10.0, 2.5, -5.0, 0.0, 7.0]).unwrap();
+
+        let constraint = Constraint::new(&df_good, "count");
+        let validation = Validation::new(&df_bad, &constraint);
+        assert_eq!(validation.integer_only, Some(1));
+        assert_eq!(validation.positive, Some(2));
+        assert_eq!(validation.non_negative, Some(1));
+        assert_eq!(validation.multiple_of, Some(2));
+    }
+
+    #[test]
+    fn test_table_checks() {
+        let df_good: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let df_short = df_good.head(Some(10));
+
+        let mut set = ConstraintSet::new(&df_good, "test");
+        set.modify_table("min_rows", "90%");
+        let validation = ValidationSet::new(&df_short, &set);
+        assert_eq!(validation.table.min_rows, Some(false));
+        assert_eq!(validation.table.columns, Some(true));
+        assert_eq!(validation.table.file_size, None);
+
+        let control = std::env::temp_dir().join("dvrs_control.txt");
+        std::fs::write(&control, "ROWS=4981\n").unwrap();
+        set.modify_table("expected_rows_from", control.to_str().unwrap());
+        set.modify_table("max_file_size", "1000");
+        let validation = ValidationSet::for_file(&df_good, &set, "test_data/brain_stroke.csv");
+        assert_eq!(validation.table.min_rows, Some(true));
+        assert_eq!(validation.table.row_count_matches, Some(true));
+        assert_eq!(validation.table.max_file_size, Some(false));
+
+        let output = std::env::temp_dir().join("dvrs_table_checks.csv");
+        validation.save_csv(&output).unwrap();
+        let table_csv = std::fs::read_to_string(output.with_extension("table.csv")).unwrap();
+        assert!(table_csv.contains("row_count,4981\n"));
+        assert!(table_csv.contains("max_file_size,false\n"));
+        set.modify_table("min_file_size", "10");
+        let validation = ValidationSet::new(&df_good, &set);
+        assert_eq!((validation.table.min_file_size, validation.table.max_file_size), (None, None));
+
+        let trailed = std::env::temp_dir().join("dvrs_trailer.csv");
+        let trailed = trailed.to_str().unwrap();
+        std::fs::write(trailed, "id,amount\n1,2.5\n2,3.5\n3,4.5\nTRAILER,3\n").unwrap();
+        let mut set = ConstraintSet::new(&df!("id" => &[1i64, 2, 3], "amount" => &[2.5, 3.5, 4.5]).unwrap(), "test");
+        set.modify_table("expected_rows_from", "trailer");
+        let data = set.read_csv(trailed).unwrap();
+        assert_eq!(data.shape(), (3, 2));
+        let validation = ValidationSet::for_file(&data, &set, trailed);
+        assert_eq!(validation.table.row_count_matches, Some(true));
+        assert!(!validation.has_errors(), "{:?}", validation.findings);
+    }
+
+    #[test]
+    fn test_duplicate_rows() {
+        let df_good: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let mut set = ConstraintSet::new(&df_good, "test");
+        assert!(set.table.duplicate_rows.is_some());
+
+        let df_resent = df_good.vstack(&df_good.slice(5, 2)).unwrap();
+        let validation = ValidationSet::new(&df_resent, &set);
+        assert_eq!(validation.table.duplicate_rows, Some(2));
+        assert_eq!(validation.table.duplicates, vec![(4983, 7), (4984, 8)]);
+
+        set.table.duplicate_rows = Some(DuplicateRows {
+            columns: vec![String::from("patient_id")],
+            ..Default::default()
+        });
+        let validation = ValidationSet::new(&df_good, &set);
+        assert_eq!(validation.table.duplicate_rows, None);
+        assert!(validation.table.duplicates.is_empty());
+    }
+
+    #[test]
+    fn test_outliers() {
+        let df_good: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let df_bad: DataFrame = CsvReader::from_path("test_data/brain_stroke_bad.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        let mut constraint = Constraint::new(&df_good, "avg_glucose_level");
+        let validation = Validation::new(&df_bad, &constraint);
+        assert_eq!(validation.z_score, None);
+
+        // The reference file already has the 700 outlier, the bad file adds two 1000s.
+        constraint.z_score = Some(4.0);
+        constraint.iqr_factor = Some(3.0);
+        let baseline = Validation::new(&df_good, &constraint);
+        let validation = Validation::new(&df_bad, &constraint);
+        assert_eq!(baseline.z_score, Some(1));
+        assert_eq!(validation.z_score, Some(3));
+        assert!(validation.iqr.unwrap() > baseline.iqr.unwrap());
+    }
+
+    #[test]
+    fn test_aggregates() {
+        let df_good = df!("glucose" => &[90.0, 100.0, 110.0, 120.0]).unwrap();
+        let df_bad = df!("glucose" => &[180.0, 200.0, 220.0, 240.0]).unwrap();
+
+        let options = InferenceOptions {
+            aggregate_tolerance: Some(0.1),
+            ..Default::default()
+        };
+        let constraint = Constraint::infer(&df_good, "glucose", &options);
+        let validation = Validation::new(&df_good, &constraint);
+        assert!(validation.aggregate_checks().iter().all(|(_, passed)| *passed));
+        assert!(Validation::new(&df_good.head(Some(2)), &constraint).sum.is_none());
+
+        let validation = Validation::new(&df_bad, &constraint);
+        assert_eq!(validation.mean, Some(false));
+        assert_eq!(validation.std_dev, Some(false));
+        assert_eq!(validation.distinct_count, None);
+        assert_eq!(validation.null_count, None);
+    }
+
+    #[test]
+    fn test_monotonic_and_gaps() {
+        let df_good = df!("event_id" => &[1, 2, 3, 4, 5]).unwrap();
+        let df_bad = df!("event_id" => &[1, 2, 2, 6, 5]).unwrap();
+
+        let constraint = Constraint::new(&df_good, "event_id");
+        let validation = Validation::new(&df_good, &constraint);
+        assert_eq!((validation.monotonic, validation.gaps), (Some(0), Some(0)));
+
+        let validation = Validation::new(&df_bad, &constraint);
+        assert_eq!(validation.monotonic, Some(2));
+        assert_eq!(validation.order_breaks, vec![4, 6]);
+        assert_eq!(validation.gaps, Some(2));
+
+        let df_float = df!("event_id" => &[1.5, 2.5]).unwrap();
+        let validation = Validation::new(&df_float, &constraint);
+        assert_eq!((validation.gaps, validation.gaps_applicable), (None, Some(false)));
+        assert!(validation.failures().contains(&("gaps_not_applicable", None)));
+    }
+
+    #[test]
+    fn test_string_hygiene() {
+        let df_good = df!("status" => &["active", "closed", "pending"]).unwrap();
+        let df_bad = df!("status" => &["active ", "Closed", "pending\u{7}", "pendiente ñ"]).unwrap();
+
+        let mut constraint = Constraint::new(&df_good, "status");
+        constraint.allowed_chars = Some(String::from("a-z"));
+        let validation = Validation::new(&df_bad, &constraint);
+        assert_eq!(validation.trimmed, Some(1));
+        assert_eq!(validation.case, Some(1));
+        assert_eq!(validation.ascii_only, Some(1));
+        assert_eq!(validation.allowed_chars, Some(4));
+        assert_eq!(validation.control_chars, Some(1));
+    }
+
+    #[test]
+    fn test_severity() {
+        let df_good: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let df_bad: DataFrame = CsvReader::from_path("test_data/brain_stroke_bad.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        let mut set = ConstraintSet::new(&df_good, "test");
+        for constraint in set.set.iter_mut() {
+            constraint.severity = Severity::Warning;
+        }
+        let validation = ValidationSet::new(&df_bad, &set);
+        assert!(!validation.has_errors());
+        assert!(validation.findings.iter().any(|f| f.column == "Residence_type" && f.check == "value_range"));
+
+        set.modify("hypertension", "severity.nullable", "error");
+        let validation = ValidationSet::new(&df_bad, &set);
+        assert!(validation.has_errors());
+        let first = &validation.findings[0];
+        assert_eq!((first.column.as_str(), first.check.as_str()), ("hypertension", "nullable"));
+        assert_eq!(first.failures, Some(2));
+    }
+
+    #[test]
+    fn test_validate_against_own_constraints() {
+        let df: DataFrame = CsvReader::from_path("test_data/brain_stroke.csv")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let path = std::env::temp_dir().join("dvrs_own_constraints.json");
+        let path = path.to_str().unwrap();
+        ConstraintSet::for_file(&df, &Default::default(), "test_data/brain_stroke.csv")
+            .save_json(path)
+            .unwrap();
+
+        let set = ConstraintSet::read_constraints(path).unwrap();
+        let validation = ValidationSet::for_file(&df, &set, "test_data/brain_stroke.csv");
+        assert!(!validation.has_errors(), "{:?}", validation.findings);
+
+        let df = df!("id" => &[Some(1), Some(2), Some(2), None, None]).unwrap();
+        let mut constraint = Constraint::new(&df, "id");
+        constraint.unique = true;
+        assert_eq!(Validation::new(&df, &constraint).unique, Some(2));
+    }
+
+    #[test]
+    fn test_column_aliases() {
+        let df_good = df!(
+            "Residence_type" => &["Urban", "Rural"],
+            "zip" => &["10001", "94105"]
+        )
+        .unwrap();
+        let df_renamed = df!(
+            " residence_TYPE" => &["Urban", "Suburban"],
+            "postcode" => &["10001", "94105"]
+        )
+        .unwrap();
+
+        let mut set = ConstraintSet::new(&df_good, "test");
+        set.modify("zip", "aliases", "postal_code, postcode");
+        let validation = ValidationSet::new(&df_renamed, &set);
+        assert_eq!(validation.set[0].matched_column, None);
+        assert_eq!(validation.set[1].matched_column.as_deref(), Some("postcode"));
+        assert_eq!(validation.set[1].max_length, Some(0));
+        assert_eq!(validation.table.column_names, Some(true));
+
+        set.modify_table("name_matching", "insensitive");
+        assert_eq!(set.name_matching, NameMatching::Insensitive);
+        let validation = ValidationSet::new(&df_renamed, &set);
+        assert_eq!(validation.set[0].matched_column.as_deref(), Some(" residence_TYPE"));
+        assert_eq!(validation.set[0].value_range, Some(1));
+        assert!(validation.to_string().contains("Residence_type: matched column  residence_TYPE\n"));
+
+        set.set[0].name = String::from("zip");
+        set.set[0].aliases = vec![String::from("postal_code")];
+        let df_clash = df!("postal_code" => &["10001"], "postcode" => &["94105"]).unwrap();
+        let validation = ValidationSet::new(&df_clash, &set);
+        assert_eq!(validation.table.column_names, Some(false));
+        assert!(validation.findings.iter().any(|f| f.column == "table" && f.check == "column_names"));
+        assert!(set.row_findings(&df_clash).is_err());
+    }
 }